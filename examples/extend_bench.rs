@@ -0,0 +1,32 @@
+use ranges_ext::{RangeSetVec, RangeVecAllocOps, UnitRange};
+use std::time::Instant;
+
+// 比较逆序输入下 merge_extend 与 merge_extend_sorted 的耗时
+fn main() {
+    for count in [10_000u32, 100_000] {
+        let input: Vec<_> = (0..count)
+            .rev()
+            .map(|i| UnitRange::new(i * 10..i * 10 + 5))
+            .collect();
+
+        let mut sorted: RangeSetVec<_> = RangeSetVec::new();
+        let start = Instant::now();
+        sorted.merge_extend_sorted(input.clone()).unwrap();
+        println!(
+            "{count:>7} ranges  merge_extend_sorted: {:?}",
+            start.elapsed()
+        );
+
+        // 逐个添加是 O(n²)，只在较小的规模上运行
+        if count <= 10_000 {
+            let mut sequential: RangeSetVec<_> = RangeSetVec::new();
+            let start = Instant::now();
+            sequential.merge_extend(input).unwrap();
+            println!(
+                "{count:>7} ranges  merge_extend:        {:?}",
+                start.elapsed()
+            );
+            assert_eq!(sequential, sorted);
+        }
+    }
+}
@@ -1,9 +1,28 @@
-use core::ops::Range;
+use core::ops::{Add, Range, Sub};
 
-use crate::{RangeError, RangeExtBaseOps, RangeInfo, RangeVecAllocOps, VecOps, core_ops};
+use crate::{
+    AddOutcome, Coordinate, Cursor, Entry, InvariantError, LockableRangeInfo, MergeObserver,
+    PriorityRangeInfo, ProvenanceRangeInfo, RangeError, RangeExtBaseOps, RangeInfo,
+    RangeVecAllocOps, Relation, RemoveStats, Segment, Snapshot, VecOps, core_ops, helpers,
+};
+
+/// 启用 `fallible-alloc` 时先尝试预留空间，分配失败返回 `AllocFailed` 而不是中止
+#[inline]
+fn reserve<T: RangeInfo, U>(
+    vec: &mut alloc::vec::Vec<U>,
+    additional: usize,
+) -> Result<(), RangeError<T>> {
+    #[cfg(feature = "fallible-alloc")]
+    vec.try_reserve(additional)
+        .map_err(|_| RangeError::AllocFailed)?;
+    #[cfg(not(feature = "fallible-alloc"))]
+    let _ = (vec, additional);
+    Ok(())
+}
 
 impl<T: RangeInfo> VecOps<T> for alloc::vec::Vec<T> {
     fn push(&mut self, item: T) -> Result<(), RangeError<T>> {
+        reserve(self, 1)?;
         self.push(item);
         Ok(())
     }
@@ -28,6 +47,7 @@ impl<T: RangeInfo> VecOps<T> for alloc::vec::Vec<T> {
     }
 
     fn insert(&mut self, index: usize, item: T) -> Result<(), RangeError<T>> {
+        reserve(self, 1)?;
         self.insert(index, item);
         Ok(())
     }
@@ -35,21 +55,113 @@ impl<T: RangeInfo> VecOps<T> for alloc::vec::Vec<T> {
     fn clear(&mut self) {
         self.clear();
     }
+
+    fn reserve_items(&mut self, additional: usize) -> Result<(), RangeError<T>> {
+        reserve(self, additional)
+    }
 }
 
 impl<T: RangeInfo> RangeVecAllocOps<T> for alloc::vec::Vec<T> {
     fn merge_add(&mut self, new_info: T) -> Result<(), RangeError<T>> {
-        let mut temp = alloc::vec::Vec::new();
-        self.merge_add_with_temp(new_info, &mut temp)?;
+        // 重复添加已有元素时跳过重建，也不分配临时缓冲区
+        let Err(new_info) = core_ops::skip_identical(self.as_mut_slice(), new_info) else {
+            return Ok(());
+        };
+        merge_add_in_place(self, new_info)?;
         Ok(())
     }
 
+    fn add_and_report(&mut self, new_info: T) -> Result<Range<T::Type>, RangeError<T>> {
+        merge_add_in_place(self, new_info)
+    }
+
     fn merge_remove(&mut self, range: Range<T::Type>) -> Result<(), RangeError<T>> {
+        self.remove_with_stats(range)?;
+        Ok(())
+    }
+
+    fn remove_with_stats(&mut self, range: Range<T::Type>) -> Result<RemoveStats, RangeError<T>> {
+        if range.start >= range.end {
+            return Ok(RemoveStats::default());
+        }
+
+        // 只替换受影响的元素窗口，其余元素和已有分配保持不动
+        let (window, parts) = core_ops::remove_window(self.as_slice(), &range);
+        let stats = core_ops::remove_stats(&window, &parts);
+        replace_window(self, window, parts)?;
+
+        debug_assert_eq!(core_ops::validate_window(self.as_slice(), &range), Ok(()));
+        Ok(stats)
+    }
+
+    fn remove_taking(
+        &mut self,
+        range: Range<T::Type>,
+    ) -> Result<alloc::vec::Vec<T>, RangeError<T>> {
+        let mut taken = alloc::vec::Vec::new();
+        if range.start >= range.end {
+            return Ok(taken);
+        }
+
+        let (window, parts) = core_ops::remove_window(self.as_slice(), &range);
+        reserve(&mut taken, window.len())?;
+        taken.extend(core_ops::taken_fragments(&self[window.clone()], &range));
+        replace_window(self, window, parts)?;
+
+        debug_assert_eq!(core_ops::validate_window(self.as_slice(), &range), Ok(()));
+        Ok(taken)
+    }
+
+    fn try_merge_remove(&mut self, range: Range<T::Type>) -> Result<(), RangeError<T>> {
+        core_ops::check_removable(self.as_slice(), &range)?;
+        self.merge_remove(range)
+    }
+
+    fn merge_remove_many<I>(&mut self, ranges: I) -> Result<(), RangeError<T>>
+    where
+        I: IntoIterator<Item = Range<T::Type>>,
+    {
+        let mut ranges: alloc::vec::Vec<_> =
+            ranges.into_iter().filter(|r| r.start < r.end).collect();
+        if ranges.is_empty() || self.is_empty() {
+            return Ok(());
+        }
+        ranges.sort_unstable_by_key(|r| r.start);
+
+        let mut out = alloc::vec::Vec::new();
+        core_ops::remove_sorted(
+            self.as_slice(),
+            core_ops::coalesce(ranges.iter().cloned()),
+            &mut out,
+        )?;
+        *self = out;
+        debug_assert!(
+            ranges
+                .iter()
+                .all(|r| core_ops::validate_window(self.as_slice(), r).is_ok())
+        );
+        Ok(())
+    }
+
+    fn merge_add_observed(
+        &mut self,
+        new_info: T,
+        observer: &mut impl MergeObserver<T>,
+    ) -> Result<(), RangeError<T>> {
         let mut temp = alloc::vec::Vec::new();
-        self.merge_remove_with_temp(range, &mut temp)?;
+        self.merge_add_with_observer(new_info, &mut temp, observer)?;
         Ok(())
     }
 
+    fn merge_remove_observed(
+        &mut self,
+        range: Range<T::Type>,
+        observer: &mut impl MergeObserver<T>,
+    ) -> Result<(), RangeError<T>> {
+        let mut temp = alloc::vec::Vec::new();
+        self.merge_remove_with_observer(range, &mut temp, observer)
+    }
+
     fn merge_extend<I>(&mut self, ranges: I) -> Result<(), RangeError<T>>
     where
         I: IntoIterator<Item = T>,
@@ -61,9 +173,738 @@ impl<T: RangeInfo> RangeVecAllocOps<T> for alloc::vec::Vec<T> {
         Ok(())
     }
 
+    fn merge_extend_sorted<I>(&mut self, ranges: I) -> Result<(), RangeError<T>>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        // 保留原始顺序，退回 merge_extend 时按原顺序添加
+        let ranges = ranges.into_iter();
+        let mut sorted = alloc::vec::Vec::new();
+        reserve(&mut sorted, ranges.size_hint().0)?;
+        for item in ranges.enumerate() {
+            reserve(&mut sorted, 1)?;
+            sorted.push(item);
+        }
+        sorted.sort_by(|(_, a), (_, b)| helpers::cmp_by_start(a, b));
+
+        // 不同 kind 的输入互相重叠，或输入与已有元素的合并会改变锁定状态时，结果取决于添加顺序
+        if core_ops::has_kind_overlap(sorted.iter().map(|(_, info)| info))
+            || sorted
+                .iter()
+                .any(|(_, info)| core_ops::touches_locked(self, info))
+        {
+            sorted.sort_unstable_by_key(|(index, _)| *index);
+            return self.merge_extend(sorted.into_iter().map(|(_, info)| info));
+        }
+
+        let mut backup = alloc::vec::Vec::new();
+        reserve(&mut backup, self.len())?;
+        backup.extend_from_slice(self);
+        for (_, info) in &sorted {
+            if merge_add_in_place(self, info.clone()).is_err() {
+                // 出错位置取决于添加顺序，按原顺序重做以得到与 merge_extend 相同的结果
+                *self = backup;
+                sorted.sort_unstable_by_key(|(index, _)| *index);
+                return self.merge_extend(sorted.into_iter().map(|(_, info)| info));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn merge_extend_counted<I>(&mut self, ranges: I) -> Result<usize, (usize, RangeError<T>)>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut count = 0;
+        for info in ranges {
+            self.merge_add(info).map_err(|e| (count, e))?;
+            count += 1;
+        }
+
+        Ok(count)
+    }
+
+    fn merge_extend_atomic<I>(&mut self, ranges: I) -> Result<(), RangeError<T>>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let backup = self.clone();
+        if let Err(e) = self.merge_extend(ranges) {
+            *self = backup;
+            return Err(e);
+        }
+
+        Ok(())
+    }
+
     fn contains_point(&self, value: T::Type) -> bool {
         core_ops::contains_point(self.as_slice(), value)
     }
+
+    fn point_index(&self, value: T::Type) -> Option<usize> {
+        core_ops::point_index(self.as_slice(), value)
+    }
+
+    fn contains_any(&self, range: Range<T::Type>) -> bool {
+        core_ops::overlaps_range(self.as_slice(), &range)
+    }
+
+    fn contains_all(&self, range: Range<T::Type>) -> bool {
+        core_ops::covers_range(self.as_slice(), range)
+    }
+
+    fn validate(&self) -> Result<(), InvariantError> {
+        core_ops::validate(self.as_slice())
+    }
+
+    fn remove_by_kind(&mut self, kind: &T::Kind) {
+        self.remove_if(|elem| elem.kind() == *kind);
+    }
+
+    fn remove_if<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.retain(|elem| !f(elem));
+    }
+
+    fn pop_first(&mut self) -> Option<T> {
+        (!self.is_empty()).then(|| self.remove(0))
+    }
+
+    fn pop_last(&mut self) -> Option<T> {
+        self.pop()
+    }
+
+    fn drop_smaller_than(&mut self, min_len: T::Type)
+    where
+        T::Type: Coordinate,
+    {
+        self.retain(|elem| !core_ops::is_shorter_than(elem, min_len));
+    }
+
+    fn ranges_with_kind(&self) -> impl Iterator<Item = (Range<T::Type>, T::Kind)> {
+        core_ops::ranges_with_kind(self.as_slice())
+    }
+
+    fn count_by_kind(&self) -> impl Iterator<Item = (T::Kind, usize, T::Type)>
+    where
+        T::Type: Sub<Output = T::Type> + Add<Output = T::Type>,
+    {
+        core_ops::count_by_kind(self.as_slice())
+    }
+
+    fn collect_into<const M: usize>(
+        &self,
+        out: &mut heapless::Vec<T, M>,
+    ) -> Result<(), RangeError<T>> {
+        core_ops::collect_into(self.as_slice(), out)
+    }
+
+    fn copied_ranges<const M: usize>(
+        &self,
+    ) -> Result<heapless::Vec<Range<T::Type>, M>, RangeError<T>> {
+        core_ops::copied_ranges(self.as_slice())
+    }
+
+    fn as_element_slice(&self) -> &[T] {
+        self.as_slice()
+    }
+
+    fn merged_ranges(&self) -> alloc::vec::Vec<Range<T::Type>> {
+        core_ops::coalesce(self.iter().map(|e| e.range())).collect()
+    }
+
+    fn elements_slice(&self, bounds: Range<T::Type>) -> &[T] {
+        &self.as_slice()[core_ops::overlapping_window(self.as_slice(), &bounds)]
+    }
+
+    fn extend_sorted_disjoint<I>(&mut self, ranges: I) -> Result<(), RangeError<T>>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        core_ops::extend_sorted_disjoint(self, ranges)
+    }
+
+    fn replace_all(&mut self, elements: alloc::vec::Vec<T>) {
+        debug_assert_eq!(core_ops::validate(&elements), Ok(()));
+        *self = elements;
+    }
+
+    fn covered_by_kind(&self, range: Range<T::Type>, kind: &T::Kind) -> bool {
+        core_ops::covered_by_kind(self.as_slice(), &range, kind)
+    }
+
+    fn gaps_within(&self, bounds: Range<T::Type>) -> impl Iterator<Item = Range<T::Type>> {
+        core_ops::gaps_within(self.as_slice(), bounds)
+    }
+
+    fn uncovered_within(&self, query: Range<T::Type>) -> impl Iterator<Item = Range<T::Type>> {
+        self.gaps_within(query)
+    }
+
+    fn first_uncovered(&self, range: Range<T::Type>) -> Option<T::Type> {
+        core_ops::first_uncovered(self.as_slice(), range)
+    }
+
+    fn first_fit(&self, size: T::Type, bounds: Range<T::Type>) -> Option<Range<T::Type>>
+    where
+        T::Type: Add<Output = T::Type> + Sub<Output = T::Type>,
+    {
+        core_ops::first_fit(self.as_slice(), size, bounds)
+    }
+
+    fn best_fit(&self, size: T::Type, bounds: Range<T::Type>) -> Option<Range<T::Type>>
+    where
+        T::Type: Add<Output = T::Type> + Sub<Output = T::Type>,
+    {
+        core_ops::best_fit(self.as_slice(), size, bounds)
+    }
+
+    fn allocate<F>(
+        &mut self,
+        size: T::Type,
+        bounds: Range<T::Type>,
+        make: F,
+    ) -> Result<Range<T::Type>, RangeError<T>>
+    where
+        T::Type: Add<Output = T::Type> + Sub<Output = T::Type>,
+        F: FnOnce(Range<T::Type>) -> T,
+    {
+        let range = self.first_fit(size, bounds).ok_or(RangeError::NoFit)?;
+        self.merge_add(make(range.clone()))?;
+        Ok(range)
+    }
+
+    fn deallocate(&mut self, range: Range<T::Type>) -> Result<(), RangeError<T>> {
+        if core_ops::gaps_within(self.as_slice(), range.clone())
+            .next()
+            .is_some()
+        {
+            return Err(RangeError::NotAllocated);
+        }
+        self.merge_remove(range)
+    }
+
+    fn resize_element(
+        &mut self,
+        old: Range<T::Type>,
+        new_end: T::Type,
+    ) -> Result<(), RangeError<T>> {
+        let elem = core_ops::find_exact(self.as_slice(), &old).ok_or(RangeError::NotAllocated)?;
+        if new_end <= old.start {
+            return Err(RangeError::EmptyRange);
+        }
+        if new_end > old.end {
+            let grown = elem.clone_with_range(old.start..new_end);
+            self.merge_add(grown)
+        } else {
+            self.merge_remove(new_end..old.end)
+        }
+    }
+
+    fn relocate(&mut self, from: Range<T::Type>, to_start: T::Type) -> Result<(), RangeError<T>>
+    where
+        T::Type: Add<Output = T::Type> + Sub<Output = T::Type>,
+    {
+        if core_ops::gaps_within(self.as_slice(), from.clone())
+            .next()
+            .is_some()
+        {
+            return Err(RangeError::NotAllocated);
+        }
+
+        let backup = self.clone();
+        let parts = backup
+            .iter()
+            .filter_map(|elem| core_ops::relocated_part(elem, &from, to_start));
+        let result = self
+            .merge_remove(from.clone())
+            .and_then(|_| self.merge_extend(parts));
+        if result.is_err() {
+            *self = backup;
+        }
+
+        result
+    }
+
+    fn conflicts_for(&self, info: &T) -> alloc::vec::Vec<&T> {
+        core_ops::conflicts(self.as_slice(), info).collect()
+    }
+
+    fn preview_add(&self, info: T) -> Result<Self, RangeError<T>> {
+        let mut preview = self.clone();
+        preview.merge_add(info)?;
+        Ok(preview)
+    }
+
+    fn segments<'a>(&'a self, bounds: Range<T::Type>) -> impl Iterator<Item = Segment<'a, T>>
+    where
+        T: 'a,
+    {
+        core_ops::segments(self.as_slice(), bounds)
+    }
+
+    fn relate<'a>(&'a self, other: &'a Self) -> impl Iterator<Item = Relation<T>>
+    where
+        T: 'a,
+    {
+        core_ops::relate(self.as_slice(), other.as_slice())
+    }
+
+    fn is_disjoint_from(&self, other: &Self) -> bool {
+        core_ops::is_disjoint(self.as_slice(), other.as_slice())
+    }
+
+    fn is_subset_of(&self, other: &Self) -> bool {
+        core_ops::is_subset(self.as_slice(), other.as_slice())
+    }
+
+    fn is_subset_of_with_kind(&self, other: &Self) -> bool {
+        core_ops::is_subset_with_kind(self.as_slice(), other.as_slice())
+    }
+
+    fn merge_add_with_split_hook<F>(
+        &mut self,
+        new_info: T,
+        on_split: F,
+    ) -> Result<(), RangeError<T>>
+    where
+        F: FnMut(&mut T),
+    {
+        let mut temp = alloc::vec::Vec::new();
+        self.merge_add_with_temp_and_split_hook(new_info, &mut temp, on_split)?;
+        Ok(())
+    }
+
+    fn add_many_same_kind<I>(&mut self, ranges: I, template: &T) -> Result<(), RangeError<T>>
+    where
+        I: IntoIterator<Item = Range<T::Type>>,
+    {
+        let mut ranges: alloc::vec::Vec<_> = ranges.into_iter().collect();
+        ranges.sort_unstable_by_key(|r| r.start);
+        let runs: alloc::vec::Vec<_> = core_ops::coalesce(ranges).collect();
+        for run in &runs {
+            core_ops::check_conflicts(self, &template.clone_with_range(run.clone()))?;
+        }
+
+        // 先切开与各段重叠的不同 kind 元素，再一次扫描把各段并入集合
+        let kind = template.kind();
+        let mut cut = alloc::vec::Vec::new();
+        core_ops::remove_sorted_except(self, runs.iter().cloned(), &mut cut, |e| e.kind() == kind)?;
+        let mut out = alloc::vec::Vec::new();
+        core_ops::merge_runs(&cut, &runs, template, &mut out)?;
+        *self = out;
+        debug_assert!(
+            runs.iter()
+                .all(|r| core_ops::validate_window(self.as_slice(), r).is_ok())
+        );
+        Ok(())
+    }
+
+    fn try_reserve(
+        &mut self,
+        additional: usize,
+    ) -> Result<(), alloc::collections::TryReserveError> {
+        alloc::vec::Vec::try_reserve(self, additional)
+    }
+
+    fn compact(&mut self) -> usize {
+        let before = self.capacity();
+        self.shrink_to_fit();
+        (before - self.capacity()) * core::mem::size_of::<T>()
+    }
+
+    fn snapshot_into(&self, dst: &mut Self) {
+        dst.clear();
+        dst.extend_from_slice(self);
+    }
+
+    fn snapshot(&self) -> Snapshot<Self> {
+        Snapshot::new(self.clone())
+    }
+
+    fn restore(&mut self, snapshot: Snapshot<Self>) {
+        *self = snapshot.into_inner();
+    }
+
+    fn retain_within(&mut self, bounds: Range<T::Type>) {
+        self.retain(|elem| helpers::ranges_overlap(&elem.range(), &bounds));
+    }
+
+    fn add_ranges<F>(&mut self, ranges: &[Range<T::Type>], make: F) -> Result<(), RangeError<T>>
+    where
+        F: Fn(Range<T::Type>) -> T,
+    {
+        self.merge_extend(ranges.iter().cloned().map(make))
+    }
+
+    fn any_overlap(&self, ranges: &[Range<T::Type>]) -> bool {
+        ranges
+            .iter()
+            .any(|range| core_ops::overlaps_range(self.as_slice(), range))
+    }
+
+    fn merge_add_checked(&mut self, new_info: T, strict: bool) -> Result<(), RangeError<T>> {
+        if strict && !core_ops::validate_range(&new_info) {
+            return Err(RangeError::EmptyRange);
+        }
+        self.merge_add(new_info)
+    }
+
+    fn merge_add_strict(&mut self, new_info: T) -> Result<(), RangeError<T>> {
+        self.merge_add_checked(new_info, true)
+    }
+
+    fn merge_add_exclusive(&mut self, new_info: T) -> Result<(), RangeError<T>> {
+        core_ops::check_exclusive(self.as_slice(), &new_info)?;
+        self.merge_add(new_info)
+    }
+
+    fn merge_add_within(
+        &mut self,
+        new_info: T,
+        universe: Range<T::Type>,
+    ) -> Result<(), RangeError<T>> {
+        core_ops::check_universe(&new_info, &universe)?;
+        self.merge_add(new_info)
+    }
+
+    fn merge_add_notifying(&mut self, new_info: T) -> Result<AddOutcome<T>, RangeError<T>> {
+        if !core_ops::validate_range(&new_info) {
+            return Ok(AddOutcome::Inserted);
+        }
+
+        let range = new_info.range();
+        let kind = new_info.kind();
+        let window = core_ops::overlapping_window(self.as_slice(), &range);
+        let displaced: alloc::vec::Vec<T> = core_ops::taken_fragments(&self[window], &range)
+            .filter(|part| part.kind() != kind)
+            .collect();
+        let merges = core_ops::touches_same_kind(self.as_slice(), &new_info);
+
+        self.merge_add(new_info)?;
+        Ok(if !displaced.is_empty() {
+            AddOutcome::Overrode(displaced)
+        } else if merges {
+            AddOutcome::MergedSameKind
+        } else {
+            AddOutcome::Inserted
+        })
+    }
+
+    fn coverage_ratio(&self, bounds: Range<T::Type>) -> f64
+    where
+        T::Type: Coordinate,
+    {
+        core_ops::coverage_ratio(self.as_slice(), &bounds)
+    }
+
+    fn coalesce(&mut self) {
+        let len = core_ops::coalesce_in_place(self.as_mut_slice());
+        self.truncate(len);
+    }
+
+    fn coalesce_by<F>(&mut self, eq: F)
+    where
+        F: FnMut(&T::Kind, &T::Kind) -> bool,
+    {
+        let len = core_ops::coalesce_in_place_by(self.as_mut_slice(), eq);
+        self.truncate(len);
+    }
+
+    fn chunk_elements(&mut self, stride: T::Type) -> Result<(), RangeError<T>>
+    where
+        T::Type: Add<Output = T::Type> + Sub<Output = T::Type>,
+    {
+        let mut chunked = alloc::vec::Vec::new();
+        reserve(&mut chunked, self.len())?;
+        core_ops::chunk_elements(self.as_slice(), stride, &mut chunked)?;
+        *self = chunked;
+        Ok(())
+    }
+
+    fn pairs<'a>(&'a self) -> impl Iterator<Item = (&'a T, &'a T)>
+    where
+        T: 'a,
+    {
+        self.as_slice().windows(2).map(|pair| (&pair[0], &pair[1]))
+    }
+
+    fn kind_boundaries(&self) -> impl Iterator<Item = T::Type> {
+        self.pairs()
+            .filter(|(prev, next)| prev.kind() != next.kind())
+            .map(|(_, next)| next.range().start)
+    }
+
+    fn merged_iter(&self) -> impl Iterator<Item = Range<T::Type>> {
+        self.iter().map(|e| e.range())
+    }
+
+    fn originals_iter(&self) -> impl Iterator<Item = Range<T::Type>>
+    where
+        T: ProvenanceRangeInfo,
+    {
+        self.iter().flat_map(|e| e.originals())
+    }
+
+    fn shift_by(&mut self, delta: T::Type) -> Result<(), RangeError<T>>
+    where
+        T::Type: Coordinate,
+    {
+        core_ops::shift_by_checked(self.as_mut_slice(), delta)
+    }
+
+    fn shift_by_saturating(&mut self, delta: T::Type)
+    where
+        T::Type: Coordinate,
+    {
+        let len = core_ops::shift_by_saturating(self.as_mut_slice(), delta);
+        self.truncate(len);
+    }
+
+    fn shift_after(&mut self, pivot: T::Type, delta: T::Type) -> Result<(), RangeError<T>>
+    where
+        T::Type: Coordinate,
+    {
+        // 先预留分割所需的空间，core_ops::shift_after 会在返回右半部分之前修改元素
+        reserve(self, 1)?;
+        if let Some((index, right)) =
+            core_ops::shift_after(self.as_mut_slice(), pivot, delta, true)?
+        {
+            self.insert(index, right);
+        }
+        Ok(())
+    }
+
+    fn collapse(&mut self, range: Range<T::Type>)
+    where
+        T::Type: Coordinate,
+    {
+        let len = core_ops::collapse(self.as_mut_slice(), &range);
+        self.truncate(len);
+    }
+
+    fn add_to_end(&mut self, start: T::Type, template: &T) -> Result<(), RangeError<T>>
+    where
+        T::Type: Coordinate,
+    {
+        self.merge_add(template.clone_with_range(start..T::Type::MAX))
+    }
+
+    fn from_btreemap<F>(
+        map: alloc::collections::BTreeMap<T::Type, T::Type>,
+        make: F,
+    ) -> Result<Self, RangeError<T>>
+    where
+        F: Fn(Range<T::Type>) -> T,
+    {
+        let mut set = alloc::vec::Vec::with_capacity(map.len());
+        set.merge_extend(map.into_iter().map(|(start, end)| make(start..end)))?;
+        Ok(set)
+    }
+
+    fn to_btreemap(&self) -> alloc::collections::BTreeMap<T::Type, T::Type> {
+        self.iter()
+            .map(|elem| (elem.range().start, elem.range().end))
+            .collect()
+    }
+
+    fn cursor(&self) -> Cursor<'_, T> {
+        Cursor::new(self.as_slice())
+    }
+
+    fn entry(&mut self, range: Range<T::Type>) -> Entry<'_, Self, T> {
+        Entry::new(self, range)
+    }
+
+    fn reclassify(&mut self, range: Range<T::Type>, template: &T) -> Result<(), RangeError<T>> {
+        let backup = self.clone();
+        let result = self
+            .merge_remove(range.clone())
+            .and_then(|_| self.merge_add(template.clone_with_range(range)));
+        if result.is_err() {
+            *self = backup;
+        }
+
+        result
+    }
+
+    fn tombstone(
+        &mut self,
+        range: Range<T::Type>,
+        sentinel: T::Kind,
+        template: &T,
+    ) -> Result<(), RangeError<T>> {
+        core_ops::check_template_kind(template, sentinel)?;
+        let covered: alloc::vec::Vec<_> =
+            core_ops::covered_within(self.as_slice(), range.clone()).collect();
+        let backup = self.clone();
+        let result = self.merge_remove(range).and_then(|_| {
+            covered
+                .into_iter()
+                .try_for_each(|part| self.merge_add(template.clone_with_range(part)))
+        });
+        if result.is_err() {
+            *self = backup;
+        }
+
+        result
+    }
+
+    fn merge_with<F>(&mut self, other: &Self, resolve: F) -> Result<(), RangeError<T>>
+    where
+        F: FnMut(&T, &T) -> T,
+    {
+        let mut temp = alloc::vec::Vec::new();
+        let backup = self.clone();
+        let result = core_ops::merge_with(self, other.as_slice(), resolve, &mut temp);
+        if result.is_err() {
+            *self = backup;
+        }
+
+        result
+    }
+
+    fn merge_add_priority(&mut self, new_info: T) -> Result<(), RangeError<T>>
+    where
+        T: PriorityRangeInfo,
+    {
+        let mut temp = alloc::vec::Vec::new();
+        let backup = self.clone();
+        let result = core_ops::merge_with(
+            self,
+            core::slice::from_ref(&new_info),
+            core_ops::prefer_priority,
+            &mut temp,
+        );
+        if result.is_err() {
+            *self = backup;
+        }
+
+        result
+    }
+
+    fn set_overwritable(&mut self, range: Range<T::Type>, value: bool) -> Result<(), RangeError<T>>
+    where
+        T: LockableRangeInfo,
+    {
+        reserve(self, 2)?;
+        let pieces = core_ops::set_overwritable(self.as_mut_slice(), &range, value, usize::MAX)?;
+        for (index, piece) in pieces.into_iter().flatten() {
+            self.insert(index, piece);
+        }
+        let len = core_ops::coalesce_in_place(self.as_mut_slice());
+        self.truncate(len);
+        Ok(())
+    }
+
+    fn element_count(&self) -> usize {
+        self.len()
+    }
+
+    fn total_len(&self) -> T::Type
+    where
+        T::Type: Add<Output = T::Type> + Sub<Output = T::Type> + Default,
+    {
+        self.iter().fold(T::Type::default(), |total, elem| {
+            total + (elem.range().end - elem.range().start)
+        })
+    }
+
+    fn covered_bytes(&self) -> u64
+    where
+        T::Type: Coordinate,
+    {
+        core_ops::covered_bytes(self.as_slice())
+    }
 }
 
 impl<T: RangeInfo> RangeExtBaseOps<T> for alloc::vec::Vec<T> {}
+
+/// 用 `parts` 替换 `window` 中的元素，只移动受影响的部分
+fn replace_window<T: RangeInfo, const N: usize>(
+    set: &mut alloc::vec::Vec<T>,
+    window: Range<usize>,
+    parts: [Option<T>; N],
+) -> Result<(), RangeError<T>> {
+    // 先预留多出的元素所需的空间，之后的插入不会分配失败，集合也不会只改写一半
+    let count = parts.iter().flatten().count();
+    reserve(set, count.saturating_sub(window.len()))?;
+
+    let (mut at, mut end) = (window.start, window.end);
+    for part in parts.into_iter().flatten() {
+        if at < end {
+            set[at] = part;
+        } else {
+            set.insert(at, part);
+            end += 1;
+        }
+        at += 1;
+    }
+    set.drain(at..end);
+    Ok(())
+}
+
+/// 与 `merge_add_with_temp` 结果相同，但只改写与新区间重叠或相接的元素窗口
+///
+/// 返回新区间最终所在元素的区间。
+fn merge_add_in_place<T: RangeInfo>(
+    set: &mut alloc::vec::Vec<T>,
+    info: T,
+) -> Result<Range<T::Type>, RangeError<T>> {
+    if !core_ops::validate_range(&info) {
+        return Ok(info.range());
+    }
+    core_ops::check_conflicts(set, &info)?;
+
+    let range = info.range();
+    let kind = info.kind();
+    let mut first = set.partition_point(|e| e.range().end < range.start);
+    let mut last = first + set[first..].partition_point(|e| e.range().start <= range.end);
+    let overwritable = core_ops::merged_overwritable(&set[first..last], &info);
+
+    // 同 kind 元素并入新区间（相接而 overwritable 不同的除外），其余元素只保留新区间两侧的部分
+    let mut merged_range = range.clone();
+    let (mut before, mut after) = (None, None);
+    for elem in &set[first..last] {
+        let elem_range = elem.range();
+        if elem.kind() == kind && core_ops::absorbs(elem, &range, overwritable) {
+            merged_range.start = core::cmp::min(merged_range.start, elem_range.start);
+            merged_range.end = core::cmp::max(merged_range.end, elem_range.end);
+        } else {
+            let [left, right] = elem.split(&range);
+            before = before.or(left);
+            after = after.or(right);
+        }
+    }
+
+    // 并入的元素可能改变 overwritable，使窗口外相接的同 kind 元素也需要合并
+    if first > 0
+        && set[first - 1].kind() == kind
+        && core_ops::absorbs(&set[first - 1], &merged_range, overwritable)
+    {
+        first -= 1;
+        merged_range.start = set[first].range().start;
+    }
+    if last < set.len()
+        && set[last].kind() == kind
+        && core_ops::absorbs(&set[last], &merged_range, overwritable)
+    {
+        merged_range.end = set[last].range().end;
+        last += 1;
+    }
+
+    let absorbed = set[first..last]
+        .iter()
+        .filter(|e| e.kind() == kind && core_ops::absorbs(*e, &merged_range, overwritable));
+    let merged = core_ops::build_merged(info, merged_range.clone(), absorbed);
+
+    replace_window(set, first..last, [before, Some(merged), after])?;
+    debug_assert_eq!(
+        core_ops::validate_window(set.as_slice(), &merged_range),
+        Ok(())
+    );
+    Ok(merged_range)
+}
@@ -0,0 +1,74 @@
+use crate::{RangeError, RangeInfo, RangeVecOps, helpers};
+
+/// 链式构造 heapless 区间集合
+///
+/// 输入先收集起来，`build` 时一次性处理：不存在不同 kind 的重叠时排序后单遍合并，
+/// 否则按添加顺序逐个 `merge_add`，以保持覆盖语义。
+#[derive(Clone, Debug)]
+pub struct RangeSetBuilder<T: RangeInfo, const N: usize> {
+    items: heapless::Vec<T, N>,
+    overflow: bool,
+}
+
+impl<T: RangeInfo, const N: usize> Default for RangeSetBuilder<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: RangeInfo, const N: usize> RangeSetBuilder<T, N> {
+    pub const fn new() -> Self {
+        Self {
+            items: heapless::Vec::new(),
+            overflow: false,
+        }
+    }
+
+    /// 添加一个区间；超出容量时在 `build` 中返回 `Capacity`
+    #[allow(clippy::should_implement_trait)]
+    pub fn add(mut self, info: T) -> Self {
+        if self.items.push(info).is_err() {
+            self.overflow = true;
+        }
+        self
+    }
+
+    /// 构造集合
+    ///
+    /// 排序单遍合并时，同 kind 区间合并后的元数据取自其中起点最小的元素。
+    pub fn build(self, temp: &mut [u8]) -> Result<heapless::Vec<T, N>, RangeError<T>> {
+        if self.overflow {
+            return Err(RangeError::Capacity);
+        }
+
+        let mut sorted = self.items.clone();
+        sorted.retain(|info| info.range().start < info.range().end);
+        sorted.sort_unstable_by(helpers::cmp_by_start);
+
+        let mut set: heapless::Vec<T, N> = heapless::Vec::new();
+        for info in sorted {
+            let range = info.range();
+            match set.last_mut() {
+                Some(last)
+                    if last.range().end >= range.start
+                        && last.kind() == info.kind()
+                        && last.overwritable() == info.overwritable() =>
+                {
+                    if range.end > last.range().end {
+                        *last = last.clone_with_range(last.range().start..range.end);
+                    }
+                    last.merge_meta(&info);
+                }
+                // 不同 kind 重叠，或同 kind 但 overwritable 不同时结果取决于添加顺序，退回逐个 merge_add
+                Some(last) if last.range().end > range.start => {
+                    set.clear();
+                    set.merge_extend(self.items, temp)?;
+                    return Ok(set);
+                }
+                _ => set.push(info).map_err(|_| RangeError::Capacity)?,
+            }
+        }
+
+        Ok(set)
+    }
+}
@@ -1,6 +1,9 @@
-use core::ops::Range;
+use core::ops::{Add, Range, Sub};
 
-use crate::{RangeError, RangeInfo};
+use crate::{
+    Coordinate, InvariantError, LockableRangeInfo, PriorityRangeInfo, RangeError, RangeExtBaseOps,
+    RangeInfo, Relation, RemoveStats, Segment, VecOps,
+};
 
 /// 验证区间有效性
 #[inline]
@@ -8,51 +11,379 @@ pub fn validate_range<T: RangeInfo>(info: &T) -> bool {
     info.range().start < info.range().end
 }
 
-/// 检查区间冲突
-pub fn check_conflicts<'a, T: RangeInfo + 'a, I: IntoIterator<Item = &'a T>>(
-    elements: I,
-    new_info: &T,
-) -> Result<(), RangeError<T>> {
+/// 判断已有元素是否与新区间冲突：重叠、kind 不同且不可覆盖
+#[inline]
+pub fn is_conflict<T: RangeInfo>(elem: &T, new_range: &Range<T::Type>, new_kind: &T::Kind) -> bool {
+    crate::helpers::ranges_overlap(&elem.range(), new_range)
+        && elem.kind() != *new_kind
+        && !elem.overwritable()
+}
+
+/// 返回与新区间冲突的所有元素
+///
+/// 元素按起点有序，先二分查找第一个可能重叠的元素，越过新区间终点后停止扫描。
+pub fn conflicts<'a, T: RangeInfo>(elements: &'a [T], new_info: &T) -> impl Iterator<Item = &'a T> {
     let new_range = new_info.range();
     let new_kind = new_info.kind();
+    let first = elements.partition_point(|e| e.range().end <= new_range.start);
+    elements[first..]
+        .iter()
+        .take_while(move |elem| elem.range().start < new_range.end)
+        .filter(move |elem| is_conflict(*elem, &new_range, &new_kind))
+}
 
-    for elem in elements {
-        let elem_range = elem.range();
+/// 检查区间冲突
+pub fn check_conflicts<T: RangeInfo>(elements: &[T], new_info: &T) -> Result<(), RangeError<T>> {
+    match conflicts(elements, new_info).next() {
+        Some(elem) => Err(RangeError::Conflict {
+            new: new_info.clone(),
+            existing: elem.clone(),
+        }),
+        None => Ok(()),
+    }
+}
 
-        if !crate::helpers::ranges_overlap(&elem_range, &new_range) {
-            continue;
-        }
+/// 新区间与同 kind 元素合并后的 `overwritable`：与新区间重叠的同 kind 元素中有不可覆盖的时为
+/// false，合并不会解除已有的锁定
+pub fn merged_overwritable<T: RangeInfo>(elements: &[T], new_info: &T) -> bool {
+    let range = new_info.range();
+    let kind = new_info.kind();
+    new_info.overwritable()
+        && elements[overlapping_window(elements, &range)]
+            .iter()
+            .all(|e| e.kind() != kind || e.overwritable())
+}
 
-        if elem.kind() == new_kind {
-            continue;
+/// 同 kind 元素是否并入区间为 `range`、`overwritable` 为 `overwritable` 的合并元素：
+/// 重叠时总是并入，首尾相接时只并入 `overwritable` 相同的元素
+#[inline]
+pub fn absorbs<T: RangeInfo>(elem: &T, range: &Range<T::Type>, overwritable: bool) -> bool {
+    let r = elem.range();
+    r.end >= range.start
+        && r.start <= range.end
+        && (crate::helpers::ranges_overlap(&r, range) || elem.overwritable() == overwritable)
+}
+
+/// 构造新区间与同 kind 元素 `absorbed` 合并后的元素，区间为 `range`
+///
+/// 新区间可覆盖而 `absorbed` 中有不可覆盖的元素时，保留第一个不可覆盖的元素并吸收新区间，
+/// 否则保留新区间；其余元素都通过 `merge_meta` 吸收。
+pub fn build_merged<'a, T: RangeInfo + 'a>(
+    new_info: T,
+    range: Range<T::Type>,
+    absorbed: impl Iterator<Item = &'a T> + Clone,
+) -> T {
+    let locked = if new_info.overwritable() {
+        absorbed.clone().position(|e| !e.overwritable())
+    } else {
+        None
+    };
+    let mut merged = match locked {
+        Some(index) => {
+            let mut merged = absorbed.clone().nth(index).unwrap().clone_with_range(range);
+            merged.merge_meta(&new_info);
+            merged
+        }
+        None if range == new_info.range() => new_info,
+        None => new_info.clone_with_range(range),
+    };
+    for (index, elem) in absorbed.enumerate() {
+        if Some(index) != locked {
+            merged.merge_meta(elem);
         }
+    }
+    merged
+}
 
-        if !elem.overwritable() {
-            return Err(RangeError::Conflict {
+/// 检查新区间是否与同 kind 元素发生合并（重叠，或首尾相接且合并后的 `overwritable` 相同）
+#[cfg(feature = "alloc")]
+pub fn touches_same_kind<T: RangeInfo>(elements: &[T], new_info: &T) -> bool {
+    let range = new_info.range();
+    let kind = new_info.kind();
+    let overwritable = merged_overwritable(elements, new_info);
+    let first = elements.partition_point(|e| e.range().end < range.start);
+    elements[first..]
+        .iter()
+        .take_while(|e| e.range().start <= range.end)
+        .any(|e| e.kind() == kind && absorbs(e, &range, overwritable))
+}
+
+/// 检查非空的新区间是否完全落在 `universe` 内
+pub fn check_universe<T: RangeInfo>(
+    new_info: &T,
+    universe: &Range<T::Type>,
+) -> Result<(), RangeError<T>> {
+    let range = new_info.range();
+    if validate_range(new_info) && (range.start < universe.start || range.end > universe.end) {
+        return Err(RangeError::OutOfBounds {
+            range,
+            universe: universe.clone(),
+        });
+    }
+    Ok(())
+}
+
+/// 检查 `range` 覆盖的元素是否都可覆盖（可删除）
+///
+/// 遇到不可覆盖的元素时返回 `Conflict`，其中 `new` 为按该元素元数据构造的待删除区间。
+pub fn check_removable<T: RangeInfo>(
+    elements: &[T],
+    range: &Range<T::Type>,
+) -> Result<(), RangeError<T>> {
+    let first = elements.partition_point(|e| e.range().end <= range.start);
+    match elements[first..]
+        .iter()
+        .take_while(|elem| elem.range().start < range.end)
+        .find(|elem| !elem.overwritable())
+    {
+        Some(elem) if range.start < range.end => Err(RangeError::Conflict {
+            new: elem.clone_with_range(range.clone()),
+            existing: elem.clone(),
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// 不可覆盖的新区间只能落在空隙中：与任意已有元素重叠时返回 `Conflict`
+///
+/// 可覆盖的新区间不受影响。
+pub fn check_exclusive<T: RangeInfo>(elements: &[T], new_info: &T) -> Result<(), RangeError<T>> {
+    if new_info.overwritable() {
+        return Ok(());
+    }
+
+    let new_range = new_info.range();
+    let first = elements.partition_point(|e| e.range().end <= new_range.start);
+    match elements.get(first) {
+        Some(elem) if new_range.start < new_range.end && elem.range().start < new_range.end => {
+            Err(RangeError::Conflict {
                 new: new_info.clone(),
                 existing: elem.clone(),
-            });
+            })
         }
+        _ => Ok(()),
+    }
+}
+
+/// 将处理后的结果复制回原数组（正序），容量不足或分配失败时不修改原数组
+pub fn copy_back<T: RangeInfo>(
+    dst: &mut (impl VecOps<T> + ?Sized),
+    src: &impl VecOps<T>,
+) -> Result<(), RangeError<T>> {
+    if src.len() > dst.capacity() {
+        return Err(RangeError::Capacity);
+    }
+    // 清空前先预留空间，复制过程中不会再失败
+    dst.reserve_items(src.len().saturating_sub(dst.len()))?;
+
+    dst.clear();
+    for elem in src.as_slice() {
+        dst.push(elem.clone())?;
     }
 
     Ok(())
 }
 
 /// 查找插入位置（二分查找）
+///
+/// 存在起点相同的元素时，返回第一个相同起点元素之前的位置（下界）。
 pub fn find_insert_position<T: RangeInfo>(elements: &[T], new_range: &Range<T::Type>) -> usize {
-    elements
-        .binary_search_by(|e| {
-            if e.range().start < new_range.start {
-                core::cmp::Ordering::Less
+    elements.partition_point(|e| e.range().start < new_range.start)
+}
+
+/// 查找插入位置（二分查找），存在起点相同的元素时返回最后一个相同起点元素之后的位置（上界）
+///
+/// 按此位置依次插入时，起点相同的元素保持插入顺序。
+pub fn find_insert_position_stable<T: RangeInfo>(
+    elements: &[T],
+    new_range: &Range<T::Type>,
+) -> usize {
+    elements.partition_point(|e| e.range().start <= new_range.start)
+}
+
+/// 计算删除 `range` 时受影响的元素窗口，以及窗口首尾元素保留下来的左右片段
+///
+/// 窗口中间的元素被完整覆盖；用两个片段替换整个窗口即得到删除后的结果。
+pub fn remove_window<T: RangeInfo>(
+    elements: &[T],
+    range: &Range<T::Type>,
+) -> (Range<usize>, [Option<T>; 2]) {
+    let first = elements.partition_point(|e| e.range().end <= range.start);
+    let last = first + elements[first..].partition_point(|e| e.range().start < range.end);
+    if first == last {
+        return (first..last, [None, None]);
+    }
+
+    let [left, _] = elements[first].split(range);
+    let [_, right] = elements[last - 1].split(range);
+    (first..last, [left, right])
+}
+
+/// 返回 `window` 内各元素落在 `range` 中的部分，保留原元素的 kind 等信息
+#[cfg(feature = "alloc")]
+pub fn taken_fragments<'a, T: RangeInfo>(
+    window: &'a [T],
+    range: &'a Range<T::Type>,
+) -> impl Iterator<Item = T> + 'a {
+    window.iter().map(move |e| {
+        let r = e.range();
+        let start = core::cmp::max(r.start, range.start);
+        let end = core::cmp::min(r.end, range.end);
+        e.clone_with_range(start..end)
+    })
+}
+
+/// 根据 `remove_window` 的结果计算删除对元素数量的影响
+pub fn remove_stats<T>(window: &Range<usize>, parts: &[Option<T>; 2]) -> RemoveStats {
+    let width = window.end - window.start;
+    let kept = parts.iter().flatten().count();
+    let split = usize::from(width == 1 && kept == 2);
+    RemoveStats {
+        removed: width + split - kept,
+        split,
+        remaining_delta: kept as isize - width as isize,
+    }
+}
+
+/// 从 elements 中删除 ranges 覆盖的部分，剩余片段按顺序写入 out；ranges 必须按起点排序且互不重叠
+///
+/// 只扫描一遍 elements 和 ranges。
+pub fn remove_sorted<T: RangeInfo>(
+    elements: &[T],
+    ranges: impl IntoIterator<Item = Range<T::Type>>,
+    out: &mut impl VecOps<T>,
+) -> Result<(), RangeError<T>> {
+    remove_sorted_except(elements, ranges, out, |_| false)
+}
+
+/// 同 `remove_sorted`，但 `keep` 返回 true 的元素原样保留，不被切开
+pub fn remove_sorted_except<T: RangeInfo>(
+    elements: &[T],
+    ranges: impl IntoIterator<Item = Range<T::Type>>,
+    out: &mut impl VecOps<T>,
+    mut keep: impl FnMut(&T) -> bool,
+) -> Result<(), RangeError<T>> {
+    let mut ranges = ranges.into_iter().peekable();
+    for elem in elements {
+        if keep(elem) {
+            out.push(elem.clone())?;
+            continue;
+        }
+        let r = elem.range();
+        while ranges.next_if(|cut| cut.end <= r.start).is_some() {}
+
+        // 依次用每个删除区间切开剩余部分
+        let mut rest = Some(elem.clone());
+        while let Some(piece) = &rest {
+            let Some(cut) = ranges.peek().filter(|cut| cut.start < r.end).cloned() else {
+                break;
+            };
+            let [left, right] = piece.split(&cut);
+            if let Some(left) = left {
+                out.push(left)?;
+            }
+            rest = right;
+            // 跨过元素末尾的删除区间可能还会影响下一个元素，暂不消耗
+            if cut.end > r.end {
+                break;
+            }
+            ranges.next();
+        }
+
+        if let Some(rest) = rest {
+            out.push(rest)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// 一次扫描将共享 `template` 元数据的区间 `runs` 并入 `elements`，结果按顺序写入 `out`
+///
+/// `runs` 必须按起点排序且互不重叠也不相接，`elements` 中与 `runs` 重叠的不同 kind 部分必须已经
+/// 删除（见 `remove_sorted_except`），冲突由调用方预先检查。结果与按顺序逐个 merge_add
+/// `template.clone_with_range(run)` 相同：通过重叠连在一起的 run 与同 kind 元素合并为一个元素，
+/// 它再与首尾相接、`overwritable` 相同的同 kind 元素合并。
+pub fn merge_runs<T: RangeInfo>(
+    elements: &[T],
+    runs: &[Range<T::Type>],
+    template: &T,
+    out: &mut impl VecOps<T>,
+) -> Result<(), RangeError<T>> {
+    let kind = template.kind();
+    let mut last_merged = false;
+    let mut i = 0;
+    let mut runs = runs.iter().peekable();
+    while let Some(run) = runs.next() {
+        while i < elements.len() && elements[i].range().end <= run.start {
+            push_merging(out, elements[i].clone(), false, &mut last_merged)?;
+            i += 1;
+        }
+
+        // 同 kind 元素可能跨过多个 run，把它们连成一段
+        let first = i;
+        let mut range = run.clone();
+        loop {
+            if i < elements.len() && elements[i].range().start < range.end {
+                debug_assert!(elements[i].kind() == kind);
+                range.start = core::cmp::min(range.start, elements[i].range().start);
+                range.end = core::cmp::max(range.end, elements[i].range().end);
+                i += 1;
+            } else if let Some(next) = runs.next_if(|next| next.start < range.end) {
+                range.end = core::cmp::max(range.end, next.end);
             } else {
-                core::cmp::Ordering::Greater
+                break;
             }
-        })
-        .unwrap_or_else(|pos| pos)
+        }
+
+        let merged = build_merged(
+            template.clone_with_range(range.clone()),
+            range,
+            elements[first..i].iter(),
+        );
+        push_merging(out, merged, true, &mut last_merged)?;
+    }
+
+    for elem in &elements[i..] {
+        push_merging(out, elem.clone(), false, &mut last_merged)?;
+    }
+    Ok(())
 }
 
-/// 检查点是否包含在任意区间中
-pub fn contains_point<T: RangeInfo>(elements: &[T], value: T::Type) -> bool {
+/// 追加元素；它与末尾元素首尾相接、kind 与 `overwritable` 都相同，且其中之一是新合并的元素时
+/// 合并两者（可能连续向左合并多个）
+fn push_merging<T: RangeInfo>(
+    out: &mut impl VecOps<T>,
+    mut elem: T,
+    mut merged: bool,
+    last_merged: &mut bool,
+) -> Result<(), RangeError<T>> {
+    while let Some(last) = out.as_slice().last() {
+        let mergeable = (merged || *last_merged)
+            && last.range().end == elem.range().start
+            && last.kind() == elem.kind()
+            && last.overwritable() == elem.overwritable();
+        if !mergeable {
+            break;
+        }
+
+        let last = out.remove(out.len() - 1);
+        let range = last.range().start..elem.range().end;
+        // 新合并的元素作为保留元素，与 merge_add 吸收相接元素时一致
+        let (mut keep, absorbed) = if merged { (elem, last) } else { (last, elem) };
+        keep = keep.clone_with_range(range);
+        keep.merge_meta(&absorbed);
+        elem = keep;
+        merged = true;
+    }
+
+    out.push(elem)?;
+    *last_merged = merged;
+    Ok(())
+}
+
+/// 返回覆盖该点的元素下标
+pub fn point_index<T: RangeInfo>(elements: &[T], value: T::Type) -> Option<usize> {
     elements
         .binary_search_by(|e| {
             if e.range().end <= value {
@@ -63,5 +394,994 @@ pub fn contains_point<T: RangeInfo>(elements: &[T], value: T::Type) -> bool {
                 core::cmp::Ordering::Equal
             }
         })
-        .is_ok()
+        .ok()
+}
+
+/// 检查点是否包含在任意区间中
+pub fn contains_point<T: RangeInfo>(elements: &[T], value: T::Type) -> bool {
+    point_index(elements, value).is_some()
+}
+
+/// 元素长度是否小于 `min_len`；长度溢出坐标类型时视为足够长
+pub fn is_shorter_than<T: RangeInfo>(elem: &T, min_len: T::Type) -> bool
+where
+    T::Type: Coordinate,
+{
+    let r = elem.range();
+    r.end.checked_sub(r.start).is_some_and(|len| len < min_len)
+}
+
+/// 按起点排序的输入中是否存在不同 kind 的重叠（空区间不计）
+#[cfg(feature = "alloc")]
+pub fn has_kind_overlap<'a, T: RangeInfo + 'a>(sorted: impl IntoIterator<Item = &'a T>) -> bool {
+    // 输入按起点排序，已扫描部分中终点最大的一段总是最后一段
+    let mut last: Option<(T::Type, T::Kind)> = None;
+    for info in sorted {
+        let range = info.range();
+        if range.start >= range.end {
+            continue;
+        }
+        match &mut last {
+            Some((end, kind)) if *end > range.start && *kind != info.kind() => return true,
+            Some((end, kind)) if *end >= range.start && *kind == info.kind() => {
+                *end = core::cmp::max(*end, range.end);
+            }
+            _ => last = Some((range.end, info.kind())),
+        }
+    }
+    false
+}
+
+/// 非空的新区间是否与已有元素存在依赖添加顺序的交互：与不可覆盖的已有元素重叠或相接，
+/// 或自身不可覆盖且与已有元素重叠或相接
+#[cfg(feature = "alloc")]
+pub fn touches_locked<T: RangeInfo>(elements: &[T], info: &T) -> bool {
+    if !validate_range(info) {
+        return false;
+    }
+    let range = info.range();
+    let first = elements.partition_point(|e| e.range().end < range.start);
+    elements[first..]
+        .iter()
+        .take_while(|e| e.range().start <= range.end)
+        .any(|e| !info.overwritable() || !e.overwritable())
+}
+
+/// 累加所有元素的长度，以 u64 返回（超出时饱和）
+///
+/// 每个元素的长度按 i128/u128 计算，窄的有符号类型（如 i8 的 `-128..127`）也不会溢出。
+pub fn covered_bytes<T: RangeInfo>(elements: &[T]) -> u64
+where
+    T::Type: Coordinate,
+{
+    elements.iter().fold(0u64, |total, elem| {
+        let len = span_len(&elem.range());
+        total.saturating_add(u64::try_from(len).unwrap_or(u64::MAX))
+    })
+}
+
+/// 区间长度，按 i128/u128 计算，不会溢出
+fn span_len<C: Coordinate>(r: &Range<C>) -> u128 {
+    match (r.start.to_i128(), r.end.to_i128()) {
+        (Some(start), Some(end)) => end.abs_diff(start),
+        // 超出 i128 的 u128 坐标
+        _ => r
+            .end
+            .to_u128()
+            .zip(r.start.to_u128())
+            .map_or(u128::MAX, |(end, start)| end - start),
+    }
+}
+
+/// 新区间与某个元素的区间和 kind 都完全相同、且 merge_add 不会改变元素布局时，不修改集合，
+/// 只把新区间的元数据通过 `merge_meta` 并入该元素；否则原样返回新区间
+///
+/// 布局不变要求合并后的 `overwritable` 与该元素相同（新区间可覆盖，或该元素本身不可覆盖），
+/// 且两侧没有会被一并合并的同 kind 元素（例如 `chunk_elements` 留下的片段）。
+pub fn skip_identical<T: RangeInfo>(elements: &mut [T], new_info: T) -> Result<(), T> {
+    let range = new_info.range();
+    let Some(index) = point_index(elements, range.start) else {
+        return Err(new_info);
+    };
+    let existing = &elements[index];
+    let overwritable = existing.overwritable();
+    let same_layout = existing.range() == range
+        && existing.kind() == new_info.kind()
+        && (new_info.overwritable() || !overwritable)
+        && index
+            .checked_sub(1)
+            .into_iter()
+            .chain(Some(index + 1))
+            .filter_map(|i| elements.get(i))
+            .all(|e| e.kind() != new_info.kind() || !absorbs(e, &range, overwritable));
+    if !same_layout {
+        return Err(new_info);
+    }
+
+    elements[index].merge_meta(&new_info);
+    Ok(())
+}
+
+/// 检查集合不变量
+pub fn validate<T: RangeInfo>(elements: &[T]) -> Result<(), InvariantError> {
+    for (index, elem) in elements.iter().enumerate() {
+        if !validate_range(elem) {
+            return Err(InvariantError::EmptyRange { index });
+        }
+    }
+
+    for (left, pair) in elements.windows(2).enumerate() {
+        let right = left + 1;
+        let (a, b) = (pair[0].range(), pair[1].range());
+        if a.start >= b.start {
+            return Err(InvariantError::Unsorted { left, right });
+        }
+        if a.end > b.start {
+            return Err(InvariantError::Overlap { left, right });
+        }
+        if a.end == b.start
+            && pair[0].kind() == pair[1].kind()
+            && pair[0].overwritable() == pair[1].overwritable()
+        {
+            return Err(InvariantError::Unmerged { left, right });
+        }
+    }
+
+    Ok(())
+}
+
+/// 只检查与 `range` 重叠或相接的元素（即一次添加或删除改写的窗口），返回的下标相对整个集合
+///
+/// `chunk_elements` 等操作有意留下的窗口外未合并元素不会被报告，修改操作之后的断言使用它。
+pub fn validate_window<T: RangeInfo>(
+    elements: &[T],
+    range: &Range<T::Type>,
+) -> Result<(), InvariantError> {
+    let first = elements.partition_point(|e| e.range().end < range.start);
+    let last = first + elements[first..].partition_point(|e| e.range().start <= range.end);
+    validate(&elements[first..last]).map_err(|e| match e {
+        InvariantError::EmptyRange { index } => InvariantError::EmptyRange {
+            index: first + index,
+        },
+        InvariantError::Unsorted { left, right } => InvariantError::Unsorted {
+            left: first + left,
+            right: first + right,
+        },
+        InvariantError::Overlap { left, right } => InvariantError::Overlap {
+            left: first + left,
+            right: first + right,
+        },
+        InvariantError::Unmerged { left, right } => InvariantError::Unmerged {
+            left: first + left,
+            right: first + right,
+        },
+    })
+}
+
+/// 预测 merge_add 之后的元素数量，不检查冲突
+pub fn predict_merge_add<T: RangeInfo>(elements: &[T], new_info: &T) -> usize {
+    if !validate_range(new_info) {
+        return elements.len();
+    }
+
+    let new_range = new_info.range();
+    let new_kind = new_info.kind();
+
+    // 分割阶段之后保留下来的元素（按顺序）
+    let kept = || {
+        elements
+            .iter()
+            .flat_map(|elem| {
+                if !crate::helpers::ranges_overlap(&elem.range(), &new_range)
+                    || elem.kind() == new_kind
+                {
+                    [Some(elem.clone()), None]
+                } else {
+                    elem.split(&new_range)
+                }
+            })
+            .flatten()
+    };
+
+    let kept_len = kept().count();
+    let insert_at = kept().filter(|e| e.range().start < new_range.start).count();
+
+    let overwritable = merged_overwritable(elements, new_info);
+    let mut merged_range = new_range.clone();
+    let mut merged = 0;
+
+    for left in kept().rev().skip(kept_len - insert_at) {
+        if left.kind() != new_kind || !absorbs(&left, &merged_range, overwritable) {
+            break;
+        }
+        merged_range.start = core::cmp::min(merged_range.start, left.range().start);
+        merged += 1;
+    }
+
+    for right in kept().skip(insert_at) {
+        if right.kind() != new_kind || !absorbs(&right, &merged_range, overwritable) {
+            break;
+        }
+        merged_range.end = core::cmp::max(merged_range.end, right.range().end);
+        merged += 1;
+    }
+
+    kept_len - merged + 1
+}
+
+/// 依次返回每个元素的 (区间, kind)
+pub fn ranges_with_kind<T: RangeInfo>(
+    elements: &[T],
+) -> impl Iterator<Item = (Range<T::Type>, T::Kind)> + '_ {
+    elements.iter().map(|e| (e.range(), e.kind()))
+}
+
+/// 按连续相同的 kind 分组，返回每组的 (kind, 元素数量, 覆盖长度)
+pub fn count_by_kind<T: RangeInfo>(
+    elements: &[T],
+) -> impl Iterator<Item = (T::Kind, usize, T::Type)> + '_
+where
+    T::Type: Sub<Output = T::Type> + Add<Output = T::Type>,
+{
+    let mut iter = elements.iter().peekable();
+    core::iter::from_fn(move || {
+        let first = iter.next()?;
+        let kind = first.kind();
+        let mut count = 1;
+        let mut total = first.range().end - first.range().start;
+
+        while let Some(next) = iter.next_if(|e| e.kind() == kind) {
+            count += 1;
+            total = total + (next.range().end - next.range().start);
+        }
+
+        Some((kind, count, total))
+    })
+}
+
+/// 将元素克隆到 heapless::Vec 中，容量不足时不修改 out
+pub fn collect_into<T: RangeInfo, const M: usize>(
+    elements: &[T],
+    out: &mut heapless::Vec<T, M>,
+) -> Result<(), RangeError<T>> {
+    if elements.len() > M {
+        return Err(RangeError::Capacity);
+    }
+
+    out.clear();
+    out.extend_from_slice(elements)
+        .map_err(|_| RangeError::Capacity)
+}
+
+/// 收集每个元素的区间到 heapless::Vec 中
+pub fn copied_ranges<T: RangeInfo, const M: usize>(
+    elements: &[T],
+) -> Result<heapless::Vec<Range<T::Type>, M>, RangeError<T>> {
+    if elements.len() > M {
+        return Err(RangeError::Capacity);
+    }
+
+    Ok(elements.iter().map(|e| e.range()).collect())
+}
+
+/// 追加已排序且互不重叠的区间，只与当前末尾元素合并
+pub fn extend_sorted_disjoint<T: RangeInfo, I: IntoIterator<Item = T>>(
+    set: &mut (impl VecOps<T> + ?Sized),
+    ranges: I,
+) -> Result<(), RangeError<T>> {
+    for info in ranges {
+        if !validate_range(&info) {
+            continue;
+        }
+
+        let Some(last) = set.as_slice().last() else {
+            set.push(info)?;
+            continue;
+        };
+
+        debug_assert!(
+            last.range().end <= info.range().start,
+            "extend_sorted_disjoint: input must be sorted and start at or after the set's end"
+        );
+
+        if last.range().end == info.range().start
+            && last.kind() == info.kind()
+            && last.overwritable() == info.overwritable()
+        {
+            let mut merged = info.clone_with_range(last.range().start..info.range().end);
+            merged.merge_meta(&set.remove(set.len() - 1));
+            set.push(merged)?;
+        } else {
+            set.push(info)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// 检查区间是否完全被指定 kind 的元素覆盖
+pub fn covered_by_kind<T: RangeInfo>(
+    elements: &[T],
+    range: &Range<T::Type>,
+    kind: &T::Kind,
+) -> bool {
+    if range.start >= range.end {
+        return true;
+    }
+
+    let first = elements.partition_point(|e| e.range().end <= range.start);
+    let mut cursor = range.start;
+
+    for elem in &elements[first..] {
+        let elem_range = elem.range();
+        if elem_range.start > cursor || elem.kind() != *kind {
+            return false;
+        }
+        cursor = elem_range.end;
+        if cursor >= range.end {
+            return true;
+        }
+    }
+
+    false
+}
+
+/// 返回 bounds 内未被任何元素覆盖的空隙（按顺序）
+pub fn gaps_within<T: RangeInfo>(
+    elements: &[T],
+    bounds: Range<T::Type>,
+) -> impl Iterator<Item = Range<T::Type>> + '_ {
+    let first = elements.partition_point(|e| e.range().end <= bounds.start);
+    let mut iter = elements[first..].iter();
+    let mut cursor = bounds.start;
+    let mut done = bounds.start >= bounds.end;
+
+    core::iter::from_fn(move || {
+        while !done {
+            let next = iter.next().map(|e| e.range());
+            let Some(elem_range) = next.filter(|r| r.start < bounds.end) else {
+                done = true;
+                return (cursor < bounds.end).then_some(cursor..bounds.end);
+            };
+
+            let gap = cursor..elem_range.start;
+            cursor = core::cmp::max(cursor, elem_range.end);
+            if gap.start < gap.end {
+                return Some(gap);
+            }
+        }
+        None
+    })
+}
+
+/// 返回 range 内第一个未被覆盖的坐标，完全覆盖（或 range 为空）时返回 `None`
+pub fn first_uncovered<T: RangeInfo>(elements: &[T], range: Range<T::Type>) -> Option<T::Type> {
+    gaps_within(elements, range).next().map(|gap| gap.start)
+}
+
+/// 检查 range 是否被完全覆盖，range 为空时视为覆盖
+pub fn covers_range<T: RangeInfo>(elements: &[T], range: Range<T::Type>) -> bool {
+    first_uncovered(elements, range).is_none()
+}
+
+/// 查找 bounds 内第一个长度不小于 size 的空隙，返回从空隙起点开始、长度为 size 的区间
+pub fn first_fit<T: RangeInfo>(
+    elements: &[T],
+    size: T::Type,
+    bounds: Range<T::Type>,
+) -> Option<Range<T::Type>>
+where
+    T::Type: Add<Output = T::Type> + Sub<Output = T::Type>,
+{
+    gaps_within(elements, bounds)
+        .find(|gap| gap.end - gap.start >= size)
+        .map(|gap| gap.start..gap.start + size)
+}
+
+/// 查找 bounds 内能容纳 size 的最小空隙（长度相同时取起点最小者），返回从其起点开始长度为 size 的区间
+pub fn best_fit<T: RangeInfo>(
+    elements: &[T],
+    size: T::Type,
+    bounds: Range<T::Type>,
+) -> Option<Range<T::Type>>
+where
+    T::Type: Add<Output = T::Type> + Sub<Output = T::Type>,
+{
+    let mut best: Option<Range<T::Type>> = None;
+    for gap in gaps_within(elements, bounds) {
+        let len = gap.end - gap.start;
+        if len < size {
+            continue;
+        }
+        if best.as_ref().is_none_or(|b| len < b.end - b.start) {
+            best = Some(gap);
+        }
+    }
+
+    best.map(|gap| gap.start..gap.start + size)
+}
+
+/// 查找区间与 range 完全相同的元素
+pub fn find_exact<'a, T: RangeInfo>(elements: &'a [T], range: &Range<T::Type>) -> Option<&'a T> {
+    elements
+        .binary_search_by(|e| e.range().start.cmp(&range.start))
+        .ok()
+        .map(|index| &elements[index])
+        .filter(|e| e.range().end == range.end)
+}
+
+/// 截取元素与 from 的交集，并平移到以 to_start 为起点的位置
+pub fn relocated_part<T: RangeInfo>(elem: &T, from: &Range<T::Type>, to_start: T::Type) -> Option<T>
+where
+    T::Type: Add<Output = T::Type> + Sub<Output = T::Type>,
+{
+    let elem_range = elem.range();
+    if !crate::helpers::ranges_overlap(&elem_range, from) {
+        return None;
+    }
+
+    let start = core::cmp::max(elem_range.start, from.start) - from.start + to_start;
+    let end = core::cmp::min(elem_range.end, from.end) - from.start + to_start;
+    Some(elem.clone_with_range(start..end))
+}
+
+/// 按顺序遍历 bounds 内的已覆盖元素和空隙
+pub fn segments<T: RangeInfo>(
+    elements: &[T],
+    bounds: Range<T::Type>,
+) -> impl Iterator<Item = Segment<'_, T>> {
+    let first = elements.partition_point(|e| e.range().end <= bounds.start);
+    let mut iter = elements[first..]
+        .iter()
+        .take_while(move |e| e.range().start < bounds.end)
+        .peekable();
+    let mut cursor = bounds.start;
+
+    core::iter::from_fn(move || {
+        if cursor >= bounds.end {
+            return None;
+        }
+
+        match iter.peek() {
+            Some(elem) if elem.range().start > cursor => {
+                let gap = cursor..elem.range().start;
+                cursor = elem.range().start;
+                Some(Segment::Gap(gap))
+            }
+            Some(_) => {
+                let elem = iter.next()?;
+                cursor = elem.range().end;
+                Some(Segment::Covered(elem))
+            }
+            None => {
+                let gap = cursor..bounds.end;
+                cursor = bounds.end;
+                Some(Segment::Gap(gap))
+            }
+        }
+    })
+}
+
+/// 同时扫描两个集合，按顺序产生至少一侧覆盖的片段
+pub fn relate<'a, T: RangeInfo>(
+    left: &'a [T],
+    right: &'a [T],
+) -> impl Iterator<Item = Relation<T>> + 'a {
+    let (mut i, mut j) = (0, 0);
+    // 已输出部分的终点，之前的坐标不再参与比较
+    let mut cursor: Option<T::Type> = None;
+    let clip = |start: T::Type, cursor: Option<T::Type>| cursor.map_or(start, |c| c.max(start));
+
+    core::iter::from_fn(move || {
+        let (relation, end) = match (left.get(i), right.get(j)) {
+            (None, None) => return None,
+            (Some(a), None) => {
+                let r = a.range();
+                i += 1;
+                let range = clip(r.start, cursor)..r.end;
+                (
+                    Relation::OnlyLeft {
+                        range,
+                        kind: a.kind(),
+                    },
+                    r.end,
+                )
+            }
+            (None, Some(b)) => {
+                let r = b.range();
+                j += 1;
+                let range = clip(r.start, cursor)..r.end;
+                (
+                    Relation::OnlyRight {
+                        range,
+                        kind: b.kind(),
+                    },
+                    r.end,
+                )
+            }
+            (Some(a), Some(b)) => {
+                let (ra, rb) = (a.range(), b.range());
+                let a_start = clip(ra.start, cursor);
+                let b_start = clip(rb.start, cursor);
+                if a_start < b_start {
+                    let end = ra.end.min(b_start);
+                    i += usize::from(end == ra.end);
+                    let range = a_start..end;
+                    (
+                        Relation::OnlyLeft {
+                            range,
+                            kind: a.kind(),
+                        },
+                        end,
+                    )
+                } else if b_start < a_start {
+                    let end = rb.end.min(a_start);
+                    j += usize::from(end == rb.end);
+                    let range = b_start..end;
+                    (
+                        Relation::OnlyRight {
+                            range,
+                            kind: b.kind(),
+                        },
+                        end,
+                    )
+                } else {
+                    let end = ra.end.min(rb.end);
+                    i += usize::from(end == ra.end);
+                    j += usize::from(end == rb.end);
+                    let relation = Relation::Both {
+                        range: a_start..end,
+                        left_kind: a.kind(),
+                        right_kind: b.kind(),
+                    };
+                    (relation, end)
+                }
+            }
+        };
+        cursor = Some(end);
+        Some(relation)
+    })
+}
+
+/// 判断两个集合是否没有公共覆盖点（不考虑 kind）
+pub fn is_disjoint<T: RangeInfo>(a: &[T], b: &[T]) -> bool {
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        let (ra, rb) = (a[i].range(), b[j].range());
+        if crate::helpers::ranges_overlap(&ra, &rb) {
+            return false;
+        }
+        if ra.end <= rb.end {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+
+    true
+}
+
+/// 判断 a 的每个覆盖点是否都被 b 覆盖（不考虑 kind）
+pub fn is_subset<T: RangeInfo>(a: &[T], b: &[T]) -> bool {
+    is_subset_by(a, b, |_, _| true)
+}
+
+/// 判断 a 的每个覆盖点是否都被 b 中 kind 相同的元素覆盖
+pub fn is_subset_with_kind<T: RangeInfo>(a: &[T], b: &[T]) -> bool {
+    is_subset_by(a, b, |x, y| x.kind() == y.kind())
+}
+
+/// 判断 a 的每个覆盖点是否都被 b 覆盖，且对齐的每一段都满足 matches(a 中元素, b 中元素)
+fn is_subset_by<T: RangeInfo>(a: &[T], b: &[T], matches: impl Fn(&T, &T) -> bool) -> bool {
+    let mut j = 0;
+    for elem in a {
+        let elem_range = elem.range();
+        let mut cursor = elem_range.start;
+        while cursor < elem_range.end {
+            while j < b.len() && b[j].range().end <= cursor {
+                j += 1;
+            }
+            match b.get(j) {
+                Some(cover) if cover.range().start <= cursor && matches(elem, cover) => {
+                    cursor = cover.range().end
+                }
+                _ => return false,
+            }
+        }
+    }
+
+    true
+}
+
+/// 合并相邻的重叠或首尾相接的区间，跳过空区间；输入有序时输出互不相接
+pub fn coalesce<R: Ord + Copy, I: IntoIterator<Item = Range<R>>>(
+    ranges: I,
+) -> impl Iterator<Item = Range<R>> {
+    let mut iter = ranges.into_iter().filter(|r| r.start < r.end).peekable();
+    core::iter::from_fn(move || {
+        let mut run = iter.next()?;
+        while let Some(next) = iter.next_if(|r| r.start <= run.end && run.start <= r.end) {
+            run.start = core::cmp::min(run.start, next.start);
+            run.end = core::cmp::max(run.end, next.end);
+        }
+        Some(run)
+    })
+}
+
+/// 返回与 bounds 重叠的元素的下标范围，bounds 为空时返回空范围
+pub fn overlapping_window<T: RangeInfo>(elements: &[T], bounds: &Range<T::Type>) -> Range<usize> {
+    let first = elements.partition_point(|e| e.range().end <= bounds.start);
+    if bounds.start >= bounds.end {
+        return first..first;
+    }
+
+    first..first + elements[first..].partition_point(|e| e.range().start < bounds.end)
+}
+
+/// 检查模板元素的 kind 是否为 `expected`
+pub fn check_template_kind<T: RangeInfo>(
+    template: &T,
+    expected: T::Kind,
+) -> Result<(), RangeError<T>> {
+    let found = template.kind();
+    if found != expected {
+        return Err(RangeError::KindMismatch { expected, found });
+    }
+    Ok(())
+}
+
+/// bounds 内被覆盖的部分（已截断到 bounds 内），首尾相接的元素不论 kind 都合并为一段
+pub fn covered_within<T: RangeInfo>(
+    elements: &[T],
+    bounds: Range<T::Type>,
+) -> impl Iterator<Item = Range<T::Type>> + '_ {
+    let window = overlapping_window(elements, &bounds);
+    coalesce(elements[window].iter().map(move |e| {
+        let r = e.range();
+        r.start.max(bounds.start)..r.end.min(bounds.end)
+    }))
+}
+
+/// 检查区间是否与任意元素重叠（二分查找）
+pub fn overlaps_range<T: RangeInfo>(elements: &[T], range: &Range<T::Type>) -> bool {
+    if range.start >= range.end {
+        return false;
+    }
+
+    let first = elements.partition_point(|e| e.range().end <= range.start);
+    elements
+        .get(first)
+        .is_some_and(|e| e.range().start < range.end)
+}
+
+/// 计算 bounds 内被覆盖部分所占的比例，bounds 为空时返回 0.0
+///
+/// 长度按 u128 累加后再转换为 f64，宽的窗口和 `u64`、`i64` 等坐标也不会溢出。
+pub fn coverage_ratio<T: RangeInfo>(elements: &[T], bounds: &Range<T::Type>) -> f64
+where
+    T::Type: Coordinate,
+{
+    if bounds.start >= bounds.end {
+        return 0.0;
+    }
+
+    let first = elements.partition_point(|e| e.range().end <= bounds.start);
+    let covered: u128 = elements[first..]
+        .iter()
+        .map(|e| e.range())
+        .take_while(|r| r.start < bounds.end)
+        .map(|r| {
+            let start = core::cmp::max(r.start, bounds.start);
+            let end = core::cmp::min(r.end, bounds.end);
+            span_len(&(start..end))
+        })
+        .sum();
+
+    covered as f64 / span_len(bounds) as f64
+}
+
+/// 原地合并首尾相接、kind 与 `overwritable` 都相同的相邻元素，返回合并后的元素数量（调用方负责截断）
+pub fn coalesce_in_place<T: RangeInfo>(elements: &mut [T]) -> usize {
+    coalesce_elements(elements, |a, b| {
+        a.kind() == b.kind() && a.overwritable() == b.overwritable()
+    })
+}
+
+/// 原地合并首尾相接且 kind 满足 `eq` 的相邻元素，合并结果沿用左侧元素的 kind，
+/// 返回合并后的元素数量（调用方负责截断）
+pub fn coalesce_in_place_by<T, F>(elements: &mut [T], mut eq: F) -> usize
+where
+    T: RangeInfo,
+    F: FnMut(&T::Kind, &T::Kind) -> bool,
+{
+    coalesce_elements(elements, |a, b| eq(&a.kind(), &b.kind()))
+}
+
+fn coalesce_elements<T, F>(elements: &mut [T], mut mergeable: F) -> usize
+where
+    T: RangeInfo,
+    F: FnMut(&T, &T) -> bool,
+{
+    if elements.is_empty() {
+        return 0;
+    }
+
+    let mut write = 0;
+    for read in 1..elements.len() {
+        let (prev, next) = (elements[write].range(), elements[read].range());
+        if prev.end == next.start && mergeable(&elements[write], &elements[read]) {
+            let (head, tail) = elements.split_at_mut(read);
+            head[write] = head[write].clone_with_range(prev.start..next.end);
+            head[write].merge_meta(&tail[0]);
+        } else {
+            write += 1;
+            elements.swap(write, read);
+        }
+    }
+
+    write + 1
+}
+
+/// 将长度超过 stride 的元素按 stride 切分成连续的片段，写入 out
+pub fn chunk_elements<T: RangeInfo>(
+    elements: &[T],
+    stride: T::Type,
+    out: &mut impl VecOps<T>,
+) -> Result<(), RangeError<T>>
+where
+    T::Type: Add<Output = T::Type> + Sub<Output = T::Type>,
+{
+    for elem in elements {
+        let elem_range = elem.range();
+        let mut start = elem_range.start;
+        while elem_range.end - start > stride && start + stride > start {
+            out.push(elem.clone_with_range(start..start + stride))?;
+            start = start + stride;
+        }
+        out.push(elem.clone_with_range(start..elem_range.end))?;
+    }
+
+    Ok(())
+}
+
+/// 将所有元素平移 delta；任一坐标（起点或终点）溢出时返回 `Overflow` 且不修改元素
+pub fn shift_by_checked<T: RangeInfo>(
+    elements: &mut [T],
+    delta: T::Type,
+) -> Result<(), RangeError<T>>
+where
+    T::Type: Coordinate,
+{
+    // 负的 delta 可能让起点越过下界，两个端点都要检查
+    let overflow = elements.iter().any(|e| {
+        let r = e.range();
+        r.start.checked_add(delta).is_none() || r.end.checked_add(delta).is_none()
+    });
+    if overflow {
+        return Err(RangeError::Overflow);
+    }
+
+    for elem in elements.iter_mut() {
+        let r = elem.range();
+        *elem = elem.clone_with_range(r.start.saturating_add(delta)..r.end.saturating_add(delta));
+    }
+
+    Ok(())
+}
+
+/// 将起点不小于 `pivot` 的元素平移 delta；跨越 `pivot` 的元素原地截断为左半部分，
+/// 返回平移后的右半部分及其插入位置（由调用方插入）
+///
+/// `delta` 不大于 0 时不做任何事。坐标溢出，或需要分割而 `can_split` 为 false 时，
+/// 分别返回 `Overflow` / `Capacity` 且不修改元素。
+pub fn shift_after<T: RangeInfo>(
+    elements: &mut [T],
+    pivot: T::Type,
+    delta: T::Type,
+    can_split: bool,
+) -> Result<Option<(usize, T)>, RangeError<T>>
+where
+    T::Type: Coordinate,
+{
+    let first = elements.partition_point(|e| e.range().end <= pivot);
+    if first == elements.len() || pivot.saturating_add(delta) <= pivot {
+        return Ok(None);
+    }
+
+    let last_end = elements[elements.len() - 1].range().end;
+    if last_end.checked_add(delta).is_none() {
+        return Err(RangeError::Overflow);
+    }
+    let straddled = elements[first].range();
+    let straddle = straddled.start < pivot;
+    if straddle && !can_split {
+        return Err(RangeError::Capacity);
+    }
+
+    let shifted_from = first + usize::from(straddle);
+    for elem in elements[shifted_from..].iter_mut() {
+        let r = elem.range();
+        *elem = elem.clone_with_range(r.start.saturating_add(delta)..r.end.saturating_add(delta));
+    }
+    if !straddle {
+        return Ok(None);
+    }
+
+    // 跨越 pivot 的元素经 `split` 拆为 [start, pivot) 和 [pivot, end)，右半部分再后移 delta
+    let [Some(left), Some(right)] = elements[first].split(&(pivot..pivot)) else {
+        unreachable!("element straddling the pivot splits into two parts");
+    };
+    let right =
+        right.clone_with_range(pivot.saturating_add(delta)..straddled.end.saturating_add(delta));
+    elements[first] = left;
+    Ok(Some((first + 1, right)))
+}
+
+/// 删除 `range` 覆盖的部分并将其后的坐标整体前移 `range` 的长度，随后合并新相接的同 kind 元素；
+/// 返回剩余元素数量（被删空的元素移到末尾，调用方负责截断）
+pub fn collapse<T: RangeInfo>(elements: &mut [T], range: &Range<T::Type>) -> usize
+where
+    T::Type: Coordinate,
+{
+    if range.start >= range.end {
+        return elements.len();
+    }
+
+    // 不小于 range.end 的坐标 x 前移到 x - range.end + range.start。宽的有符号区间的长度可能超出
+    // 类型范围，因此不先计算长度；结果落在 [range.start, x] 内，两种计算顺序中至少有一种不溢出
+    let shift_back = |x: T::Type| {
+        x.checked_sub(range.end)
+            .and_then(|v| v.checked_add(range.start))
+            .or_else(|| x.checked_add(range.start)?.checked_sub(range.end))
+            .expect("collapsed coordinate lies between range.start and the original coordinate")
+    };
+    let mut write = 0;
+    for read in 0..elements.len() {
+        let r = elements[read].range();
+        let collapsed = if r.end <= range.start {
+            r
+        } else if r.start >= range.end {
+            shift_back(r.start)..shift_back(r.end)
+        } else {
+            // 与删除区间重叠：保留两侧的部分，右侧部分前移后与左侧部分首尾相接
+            core::cmp::min(r.start, range.start)..shift_back(core::cmp::max(r.end, range.end))
+        };
+        if collapsed.start < collapsed.end {
+            elements[read] = elements[read].clone_with_range(collapsed);
+            elements.swap(write, read);
+            write += 1;
+        }
+    }
+
+    coalesce_in_place(&mut elements[..write])
+}
+
+/// 将所有元素平移 delta，坐标在类型上界处饱和；返回平移后仍非空的元素数量（空元素被移到末尾，调用方负责截断）
+pub fn shift_by_saturating<T: RangeInfo>(elements: &mut [T], delta: T::Type) -> usize
+where
+    T::Type: Coordinate,
+{
+    let mut len = 0;
+    for index in 0..elements.len() {
+        let r = elements[index].range();
+        let shifted = r.start.saturating_add(delta)..r.end.saturating_add(delta);
+        if shifted.start < shifted.end {
+            elements[index] = elements[index].clone_with_range(shifted);
+            elements.swap(len, index);
+            len += 1;
+        }
+    }
+
+    len
+}
+
+/// 修改 range 覆盖的元素的 `overwritable`；只有部分落在 range 内的元素原地截断为 range 内的部分，
+/// 返回保留原值的 range 外片段及其插入位置（按返回顺序插入，之后由调用方合并相接元素）
+///
+/// 需要插入的片段数超过 `spare` 时返回 `Capacity` 且不修改元素。
+pub fn set_overwritable<T: LockableRangeInfo>(
+    elements: &mut [T],
+    range: &Range<T::Type>,
+    value: bool,
+    spare: usize,
+) -> Result<[Option<(usize, T)>; 2], RangeError<T>> {
+    let window = overlapping_window(elements, range);
+    if window.is_empty() {
+        return Ok([None, None]);
+    }
+
+    let (first, last) = (window.start, window.end - 1);
+    let split_left =
+        elements[first].overwritable() != value && elements[first].range().start < range.start;
+    let split_right =
+        elements[last].overwritable() != value && elements[last].range().end > range.end;
+    if usize::from(split_left) + usize::from(split_right) > spare {
+        return Err(RangeError::Capacity);
+    }
+
+    let mut right = None;
+    if split_right {
+        let [_, Some(piece)] = elements[last].split(range) else {
+            unreachable!("element extending past the range leaves a right part");
+        };
+        let r = elements[last].range();
+        elements[last] = elements[last].clone_with_range(r.start..range.end);
+        right = Some((last + 1, piece));
+    }
+    let mut left = None;
+    if split_left {
+        let [Some(piece), _] = elements[first].split(range) else {
+            unreachable!("element starting before the range leaves a left part");
+        };
+        let r = elements[first].range();
+        elements[first] = elements[first].clone_with_range(range.start..r.end);
+        left = Some((first, piece));
+    }
+
+    for elem in &mut elements[window] {
+        elem.set_overwritable(value);
+    }
+    Ok([right, left])
+}
+
+/// 按优先级决定重叠子区间的归属：已有元素优先级严格更高时保留已有元素，否则保留新元素
+pub fn prefer_priority<T: PriorityRangeInfo>(existing: &T, incoming: &T) -> T {
+    if existing.priority() > incoming.priority() {
+        existing.clone()
+    } else {
+        incoming.clone()
+    }
+}
+
+/// 将 `other` 的元素逐个并入 `set`，与不同 kind 元素重叠的子区间由 `resolve(已有, 新)` 决定
+///
+/// 传给 `resolve` 的两个元素都已截断到重叠子区间，返回值同样会被截断到该子区间。
+/// 不检查 `overwritable`。出错时 `set` 可能只完成了部分合并，由调用方负责恢复。
+pub fn merge_with<T, S, F>(
+    set: &mut S,
+    other: &[T],
+    mut resolve: F,
+    temp: &mut impl VecOps<T>,
+) -> Result<(), RangeError<T>>
+where
+    T: RangeInfo,
+    S: RangeExtBaseOps<T> + ?Sized,
+    F: FnMut(&T, &T) -> T,
+{
+    for incoming in other {
+        let range = incoming.range();
+        let mut cursor = range.start;
+        while cursor < range.end {
+            let elements = set.as_slice();
+            let piece = match point_index(elements, cursor) {
+                Some(index) if elements[index].kind() != incoming.kind() => {
+                    let existing = &elements[index];
+                    let seg = cursor..core::cmp::min(existing.range().end, range.end);
+                    let winner = resolve(
+                        &existing.clone_with_range(seg.clone()),
+                        &incoming.clone_with_range(seg.clone()),
+                    );
+                    winner.clone_with_range(seg)
+                }
+                Some(index) => {
+                    let end = core::cmp::min(elements[index].range().end, range.end);
+                    incoming.clone_with_range(cursor..end)
+                }
+                None => {
+                    let next = elements.partition_point(|e| e.range().start <= cursor);
+                    let end = elements
+                        .get(next)
+                        .map_or(range.end, |e| core::cmp::min(e.range().start, range.end));
+                    incoming.clone_with_range(cursor..end)
+                }
+            };
+
+            let seg = piece.range();
+            set.merge_remove_with_temp(seg.clone(), temp)?;
+            set.merge_add_with_temp(piece, temp)?;
+            cursor = seg.end;
+        }
+    }
+
+    Ok(())
 }
@@ -0,0 +1,52 @@
+use crate::RangeInfo;
+
+/// 顺序扫描游标：记住当前位置，坐标单调递增时每次前进均摊 O(1)
+#[derive(Clone, Debug)]
+pub struct Cursor<'a, T: RangeInfo> {
+    elements: &'a [T],
+    index: usize,
+    position: Option<T::Type>,
+}
+
+impl<'a, T: RangeInfo> Cursor<'a, T> {
+    pub(crate) fn new(elements: &'a [T]) -> Self {
+        Self {
+            elements,
+            index: 0,
+            position: None,
+        }
+    }
+
+    /// 将游标前进到 `value`，返回覆盖该点的元素
+    ///
+    /// `value` 必须不小于上一次的位置，debug 模式下会断言。
+    pub fn advance_to(&mut self, value: T::Type) -> Option<&'a T> {
+        debug_assert!(
+            self.position.is_none_or(|p| p <= value),
+            "Cursor::advance_to: positions must be non-decreasing"
+        );
+
+        while self
+            .elements
+            .get(self.index)
+            .is_some_and(|e| e.range().end <= value)
+        {
+            self.index += 1;
+        }
+        self.position = Some(value);
+        self.current()
+    }
+
+    /// 返回覆盖当前位置的元素；位于空隙中或尚未前进时返回 `None`
+    pub fn current(&self) -> Option<&'a T> {
+        let position = self.position?;
+        self.elements
+            .get(self.index)
+            .filter(|e| e.range().start <= position)
+    }
+
+    /// 当前位置
+    pub fn position(&self) -> Option<T::Type> {
+        self.position
+    }
+}
@@ -0,0 +1,120 @@
+use core::ops::Range;
+
+use crate::{RangeError, RangeInfo, VecOps};
+
+/// `entry` 的结果：`range.start` 已被覆盖时为 `Occupied`，否则为 `Vacant`
+pub enum Entry<'a, S, T: RangeInfo> {
+    /// 覆盖 `range.start` 的已有元素
+    Occupied(&'a T),
+    /// `range.start` 位于空隙中
+    Vacant(VacantEntry<'a, S, T>),
+}
+
+/// 尚未被覆盖的位置，可在此插入新元素
+pub struct VacantEntry<'a, S, T: RangeInfo> {
+    set: &'a mut S,
+    index: usize,
+    range: Range<T::Type>,
+}
+
+impl<'a, S: VecOps<T>, T: RangeInfo> Entry<'a, S, T> {
+    pub(crate) fn new(set: &'a mut S, range: Range<T::Type>) -> Self {
+        let index = set
+            .as_slice()
+            .partition_point(|e| e.range().start <= range.start);
+        match index.checked_sub(1).map(|i| &set.as_slice()[i]) {
+            Some(elem) if range.start < elem.range().end => {
+                let set: &'a S = set;
+                Entry::Occupied(&set.as_slice()[index - 1])
+            }
+            _ => Entry::Vacant(VacantEntry { set, index, range }),
+        }
+    }
+
+    /// 已覆盖时返回已有元素；否则以 `make(range)` 构造新元素插入，规则见 [`VacantEntry::insert`]
+    pub fn or_insert_with(
+        self,
+        make: impl FnOnce(Range<T::Type>) -> T,
+    ) -> Result<&'a T, RangeError<T>> {
+        match self {
+            Entry::Occupied(elem) => Ok(elem),
+            Entry::Vacant(vacant) => {
+                let info = make(vacant.range.clone());
+                vacant.insert(info)
+            }
+        }
+    }
+}
+
+impl<'a, S: VecOps<T>, T: RangeInfo> VacantEntry<'a, S, T> {
+    /// 查询时传入的区间
+    pub fn range(&self) -> &Range<T::Type> {
+        &self.range
+    }
+
+    /// 在空隙中插入 `info`，并与首尾相接的同 kind 邻居合并
+    ///
+    /// `info` 只能落在查询位置所在的空隙内：超出空隙、与前后的已有覆盖部分重叠时返回 `Conflict`
+    /// （无论是否可覆盖，`existing` 为越过的邻居），空区间返回 `EmptyRange`，容量不足返回
+    /// `Capacity`；出错时集合保持不变。
+    pub fn insert(self, info: T) -> Result<&'a T, RangeError<T>> {
+        let Self { set, index, .. } = self;
+        let range = info.range();
+        if range.start >= range.end {
+            return Err(RangeError::EmptyRange);
+        }
+        if let Some(prev) = index.checked_sub(1).map(|i| &set.as_slice()[i])
+            && range.start < prev.range().end
+        {
+            return Err(RangeError::Conflict {
+                new: info,
+                existing: prev.clone(),
+            });
+        }
+        if let Some(next) = set.as_slice().get(index)
+            && next.range().start < range.end
+        {
+            return Err(RangeError::Conflict {
+                new: info,
+                existing: next.clone(),
+            });
+        }
+
+        let kind = info.kind();
+        let touches_left = index > 0 && {
+            let left = &set.as_slice()[index - 1];
+            left.range().end == range.start
+                && left.kind() == kind
+                && left.overwritable() == info.overwritable()
+        };
+        let touches_right = set.as_slice().get(index).is_some_and(|right| {
+            right.range().start == range.end
+                && right.kind() == kind
+                && right.overwritable() == info.overwritable()
+        });
+
+        let right = touches_right.then(|| set.remove(index));
+        let at = if touches_left { index - 1 } else { index };
+        let left = touches_left.then(|| set.remove(at));
+
+        let mut merged = range.clone();
+        if let Some(right) = &right {
+            merged.end = right.range().end;
+        }
+        if let Some(left) = &left {
+            merged.start = left.range().start;
+        }
+        let mut info = if merged == range {
+            info
+        } else {
+            info.clone_with_range(merged)
+        };
+        for neighbor in left.iter().chain(&right) {
+            info.merge_meta(neighbor);
+        }
+        set.insert(at, info)?;
+
+        let set: &'a S = set;
+        Ok(&set.as_slice()[at])
+    }
+}
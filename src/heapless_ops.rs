@@ -1,20 +1,35 @@
-use core::ops::Range;
+use core::ops::{Add, Range, Sub};
 
 use tinyvec::SliceVec;
 
 use crate::{
-    RangeError, RangeExtBaseOps, RangeInfo, RangeVecOps, VecOps, core_ops,
-    helpers::bytes_to_slice_mut,
+    Coordinate, Cursor, Entry, InvariantError, LockableRangeInfo, MergeObserver, PriorityRangeInfo,
+    ProvenanceRangeInfo, RangeError, RangeExtBaseOps, RangeInfo, RangeSet, RangeVecOps, Relation,
+    RemoveStats, Segment, Snapshot, VecOps, core_ops, helpers, helpers::bytes_to_slice_mut,
 };
 
 impl<T: RangeInfo, const N: usize> RangeVecOps<T> for heapless::Vec<T, N> {
     fn merge_add(&mut self, new_info: T, temp: &mut [u8]) -> Result<(), RangeError<T>> {
+        // 重复添加已有元素时跳过重建
+        let Err(new_info) = core_ops::skip_identical(self.as_mut_slice(), new_info) else {
+            return Ok(());
+        };
         let temp_buff = bytes_to_slice_mut::<T>(temp);
         let mut temp = SliceVec::from_slice_len(temp_buff, 0);
         self.merge_add_with_temp(new_info, &mut temp)?;
         Ok(())
     }
 
+    fn add_and_report(
+        &mut self,
+        new_info: T,
+        temp: &mut [u8],
+    ) -> Result<Range<T::Type>, RangeError<T>> {
+        let temp_buff = bytes_to_slice_mut::<T>(temp);
+        let mut temp = SliceVec::from_slice_len(temp_buff, 0);
+        self.merge_add_with_temp_and_split_hook(new_info, &mut temp, |_| {})
+    }
+
     fn merge_remove(
         &mut self,
         range: Range<T::Type>,
@@ -26,6 +41,88 @@ impl<T: RangeInfo, const N: usize> RangeVecOps<T> for heapless::Vec<T, N> {
         Ok(())
     }
 
+    fn remove_with_stats(
+        &mut self,
+        range: Range<T::Type>,
+        temp: &mut [u8],
+    ) -> Result<RemoveStats, RangeError<T>> {
+        if range.start >= range.end {
+            return Ok(RemoveStats::default());
+        }
+
+        let (window, parts) = core_ops::remove_window(self.as_slice(), &range);
+        self.merge_remove(range, temp)?;
+        Ok(core_ops::remove_stats(&window, &parts))
+    }
+
+    fn try_merge_remove(
+        &mut self,
+        range: Range<T::Type>,
+        temp: &mut [u8],
+    ) -> Result<(), RangeError<T>> {
+        core_ops::check_removable(self.as_slice(), &range)?;
+        self.merge_remove(range, temp)
+    }
+
+    fn merge_remove_many<I>(&mut self, ranges: I, temp: &mut [u8]) -> Result<(), RangeError<T>>
+    where
+        I: IntoIterator<Item = Range<T::Type>>,
+    {
+        let temp_buff = bytes_to_slice_mut::<T>(temp);
+        let mut temp = SliceVec::from_slice_len(temp_buff, 0);
+        let backup = self.clone();
+        let mut ranges = ranges.into_iter().filter(|r| r.start < r.end);
+        while !self.is_empty() {
+            let mut batch: heapless::Vec<Range<T::Type>, N> = ranges.by_ref().take(N).collect();
+            if batch.is_empty() {
+                break;
+            }
+            batch.sort_unstable_by_key(|r| r.start);
+
+            temp.clear();
+            let result = core_ops::remove_sorted(
+                self.as_slice(),
+                core_ops::coalesce(batch.iter().cloned()),
+                &mut temp,
+            )
+            .and_then(|_| core_ops::copy_back(self, &temp));
+            if let Err(e) = result {
+                *self = backup;
+                return Err(e);
+            }
+            debug_assert!(
+                batch
+                    .iter()
+                    .all(|r| core_ops::validate_window(self.as_slice(), r).is_ok())
+            );
+        }
+
+        Ok(())
+    }
+
+    fn merge_add_observed(
+        &mut self,
+        new_info: T,
+        observer: &mut impl MergeObserver<T>,
+        temp: &mut [u8],
+    ) -> Result<(), RangeError<T>> {
+        let temp_buff = bytes_to_slice_mut::<T>(temp);
+        let mut temp = SliceVec::from_slice_len(temp_buff, 0);
+        self.merge_add_with_observer(new_info, &mut temp, observer)?;
+        Ok(())
+    }
+
+    fn merge_remove_observed(
+        &mut self,
+        range: Range<T::Type>,
+        observer: &mut impl MergeObserver<T>,
+        temp: &mut [u8],
+    ) -> Result<(), RangeError<T>> {
+        let temp_buff = bytes_to_slice_mut::<T>(temp);
+        let mut temp = SliceVec::from_slice_len(temp_buff, 0);
+        self.merge_remove_with_observer(range, &mut temp, observer)
+    }
+
     fn merge_extend<I>(&mut self, ranges: I, temp: &mut [u8]) -> Result<(), RangeError<T>>
     where
         I: IntoIterator<Item = T>,
@@ -37,9 +134,627 @@ impl<T: RangeInfo, const N: usize> RangeVecOps<T> for heapless::Vec<T, N> {
         Ok(())
     }
 
+    fn merge_extend_counted<I>(
+        &mut self,
+        ranges: I,
+        temp: &mut [u8],
+    ) -> Result<usize, (usize, RangeError<T>)>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut count = 0;
+        for info in ranges {
+            self.merge_add(info, temp).map_err(|e| (count, e))?;
+            count += 1;
+        }
+
+        Ok(count)
+    }
+
+    fn merge_extend_atomic<I>(&mut self, ranges: I, temp: &mut [u8]) -> Result<(), RangeError<T>>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let backup = self.clone();
+        if let Err(e) = self.merge_extend(ranges, temp) {
+            *self = backup;
+            return Err(e);
+        }
+
+        Ok(())
+    }
+
     fn contains_point(&self, value: T::Type) -> bool {
         core_ops::contains_point(self.as_slice(), value)
     }
+
+    fn point_index(&self, value: T::Type) -> Option<usize> {
+        core_ops::point_index(self.as_slice(), value)
+    }
+
+    fn contains_any(&self, range: Range<T::Type>) -> bool {
+        core_ops::overlaps_range(self.as_slice(), &range)
+    }
+
+    fn contains_all(&self, range: Range<T::Type>) -> bool {
+        core_ops::covers_range(self.as_slice(), range)
+    }
+
+    fn validate(&self) -> Result<(), InvariantError> {
+        core_ops::validate(self.as_slice())
+    }
+
+    fn merge_add_predicted_len(&self, info: &T) -> usize {
+        core_ops::predict_merge_add(self.as_slice(), info)
+    }
+
+    fn would_fit(&self, info: &T) -> bool {
+        core_ops::predict_merge_add(self.as_slice(), info) <= N
+    }
+
+    fn remove_by_kind(&mut self, kind: &T::Kind) {
+        self.remove_if(|elem| elem.kind() == *kind);
+    }
+
+    fn remove_if<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.retain(|elem| !f(elem));
+    }
+
+    fn pop_first(&mut self) -> Option<T> {
+        (!self.is_empty()).then(|| self.remove(0))
+    }
+
+    fn pop_last(&mut self) -> Option<T> {
+        self.pop()
+    }
+
+    fn drop_smaller_than(&mut self, min_len: T::Type)
+    where
+        T::Type: Coordinate,
+    {
+        self.retain(|elem| !core_ops::is_shorter_than(elem, min_len));
+    }
+
+    fn ranges_with_kind(&self) -> impl Iterator<Item = (Range<T::Type>, T::Kind)> {
+        core_ops::ranges_with_kind(self.as_slice())
+    }
+
+    fn count_by_kind(&self) -> impl Iterator<Item = (T::Kind, usize, T::Type)>
+    where
+        T::Type: Sub<Output = T::Type> + Add<Output = T::Type>,
+    {
+        core_ops::count_by_kind(self.as_slice())
+    }
+
+    fn collect_into<const M: usize>(
+        &self,
+        out: &mut heapless::Vec<T, M>,
+    ) -> Result<(), RangeError<T>> {
+        core_ops::collect_into(self.as_slice(), out)
+    }
+
+    fn copied_ranges<const M: usize>(
+        &self,
+    ) -> Result<heapless::Vec<Range<T::Type>, M>, RangeError<T>> {
+        core_ops::copied_ranges(self.as_slice())
+    }
+
+    fn try_into_capacity<const M: usize>(self) -> Result<RangeSet<T, M>, Self> {
+        if self.len() > M {
+            return Err(self);
+        }
+
+        Ok(self.into_iter().collect())
+    }
+
+    fn as_element_slice(&self) -> &[T] {
+        self.as_slice()
+    }
+
+    fn elements_slice(&self, bounds: Range<T::Type>) -> &[T] {
+        &self.as_slice()[core_ops::overlapping_window(self.as_slice(), &bounds)]
+    }
+
+    fn extend_sorted_disjoint<I>(&mut self, ranges: I) -> Result<(), RangeError<T>>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        core_ops::extend_sorted_disjoint(self, ranges)
+    }
+
+    fn covered_by_kind(&self, range: Range<T::Type>, kind: &T::Kind) -> bool {
+        core_ops::covered_by_kind(self.as_slice(), &range, kind)
+    }
+
+    fn gaps_within(&self, bounds: Range<T::Type>) -> impl Iterator<Item = Range<T::Type>> {
+        core_ops::gaps_within(self.as_slice(), bounds)
+    }
+
+    fn uncovered_within(&self, query: Range<T::Type>) -> impl Iterator<Item = Range<T::Type>> {
+        self.gaps_within(query)
+    }
+
+    fn first_uncovered(&self, range: Range<T::Type>) -> Option<T::Type> {
+        core_ops::first_uncovered(self.as_slice(), range)
+    }
+
+    fn first_fit(&self, size: T::Type, bounds: Range<T::Type>) -> Option<Range<T::Type>>
+    where
+        T::Type: Add<Output = T::Type> + Sub<Output = T::Type>,
+    {
+        core_ops::first_fit(self.as_slice(), size, bounds)
+    }
+
+    fn best_fit(&self, size: T::Type, bounds: Range<T::Type>) -> Option<Range<T::Type>>
+    where
+        T::Type: Add<Output = T::Type> + Sub<Output = T::Type>,
+    {
+        core_ops::best_fit(self.as_slice(), size, bounds)
+    }
+
+    fn allocate<F>(
+        &mut self,
+        size: T::Type,
+        bounds: Range<T::Type>,
+        make: F,
+        temp: &mut [u8],
+    ) -> Result<Range<T::Type>, RangeError<T>>
+    where
+        T::Type: Add<Output = T::Type> + Sub<Output = T::Type>,
+        F: FnOnce(Range<T::Type>) -> T,
+    {
+        let range = self.first_fit(size, bounds).ok_or(RangeError::NoFit)?;
+        self.merge_add(make(range.clone()), temp)?;
+        Ok(range)
+    }
+
+    fn deallocate(&mut self, range: Range<T::Type>, temp: &mut [u8]) -> Result<(), RangeError<T>> {
+        if core_ops::gaps_within(self.as_slice(), range.clone())
+            .next()
+            .is_some()
+        {
+            return Err(RangeError::NotAllocated);
+        }
+        self.merge_remove(range, temp)
+    }
+
+    fn resize_element(
+        &mut self,
+        old: Range<T::Type>,
+        new_end: T::Type,
+        temp: &mut [u8],
+    ) -> Result<(), RangeError<T>> {
+        let elem = core_ops::find_exact(self.as_slice(), &old).ok_or(RangeError::NotAllocated)?;
+        if new_end <= old.start {
+            return Err(RangeError::EmptyRange);
+        }
+        if new_end > old.end {
+            let grown = elem.clone_with_range(old.start..new_end);
+            self.merge_add(grown, temp)
+        } else {
+            self.merge_remove(new_end..old.end, temp)
+        }
+    }
+
+    fn relocate(
+        &mut self,
+        from: Range<T::Type>,
+        to_start: T::Type,
+        temp: &mut [u8],
+    ) -> Result<(), RangeError<T>>
+    where
+        T::Type: Add<Output = T::Type> + Sub<Output = T::Type>,
+    {
+        if core_ops::gaps_within(self.as_slice(), from.clone())
+            .next()
+            .is_some()
+        {
+            return Err(RangeError::NotAllocated);
+        }
+
+        let backup = self.clone();
+        let parts = backup
+            .iter()
+            .filter_map(|elem| core_ops::relocated_part(elem, &from, to_start));
+        let result = self
+            .merge_remove(from.clone(), temp)
+            .and_then(|_| self.merge_extend(parts, temp));
+        if result.is_err() {
+            *self = backup;
+        }
+
+        result
+    }
+
+    fn preview_add(&self, info: T, out: &mut Self, temp: &mut [u8]) -> Result<(), RangeError<T>> {
+        out.clone_from(self);
+        out.merge_add(info, temp)
+    }
+
+    fn segments<'a>(&'a self, bounds: Range<T::Type>) -> impl Iterator<Item = Segment<'a, T>>
+    where
+        T: 'a,
+    {
+        core_ops::segments(self.as_slice(), bounds)
+    }
+
+    fn relate<'a>(&'a self, other: &'a Self) -> impl Iterator<Item = Relation<T>>
+    where
+        T: 'a,
+    {
+        core_ops::relate(self.as_slice(), other.as_slice())
+    }
+
+    fn is_disjoint_from(&self, other: &Self) -> bool {
+        core_ops::is_disjoint(self.as_slice(), other.as_slice())
+    }
+
+    fn is_subset_of(&self, other: &Self) -> bool {
+        core_ops::is_subset(self.as_slice(), other.as_slice())
+    }
+
+    fn is_subset_of_with_kind(&self, other: &Self) -> bool {
+        core_ops::is_subset_with_kind(self.as_slice(), other.as_slice())
+    }
+
+    fn merge_add_with_split_hook<F>(
+        &mut self,
+        new_info: T,
+        on_split: F,
+        temp: &mut [u8],
+    ) -> Result<(), RangeError<T>>
+    where
+        F: FnMut(&mut T),
+    {
+        let temp_buff = bytes_to_slice_mut::<T>(temp);
+        let mut temp = SliceVec::from_slice_len(temp_buff, 0);
+        self.merge_add_with_temp_and_split_hook(new_info, &mut temp, on_split)?;
+        Ok(())
+    }
+
+    fn add_many_same_kind<I>(
+        &mut self,
+        ranges: I,
+        template: &T,
+        temp: &mut [u8],
+    ) -> Result<(), RangeError<T>>
+    where
+        I: IntoIterator<Item = Range<T::Type>>,
+    {
+        let temp_buff = bytes_to_slice_mut::<T>(temp);
+        let mut temp = SliceVec::from_slice_len(temp_buff, 0);
+        let kind = template.kind();
+        let backup = self.clone();
+        let mut ranges = ranges.into_iter().filter(|r| r.start < r.end);
+        loop {
+            // 每批最多 N 个区间：排序合并后先检查冲突，再切开不同 kind 的元素写入 temp，
+            // 最后一次扫描把各段并入集合
+            let mut batch: heapless::Vec<Range<T::Type>, N> = ranges.by_ref().take(N).collect();
+            if batch.is_empty() {
+                break;
+            }
+            batch.sort_unstable_by_key(|r| r.start);
+            let runs: heapless::Vec<Range<T::Type>, N> = core_ops::coalesce(batch).collect();
+
+            temp.clear();
+            let result = runs
+                .iter()
+                .try_for_each(|run| {
+                    core_ops::check_conflicts(self, &template.clone_with_range(run.clone()))
+                })
+                .and_then(|_| {
+                    core_ops::remove_sorted_except(self, runs.iter().cloned(), &mut temp, |e| {
+                        e.kind() == kind
+                    })
+                })
+                .and_then(|_| {
+                    self.clear();
+                    core_ops::merge_runs(&temp, &runs, template, self)
+                });
+            if let Err(e) = result {
+                *self = backup;
+                return Err(e);
+            }
+            debug_assert!(
+                runs.iter()
+                    .all(|r| core_ops::validate_window(self.as_slice(), r).is_ok())
+            );
+        }
+
+        Ok(())
+    }
+
+    fn snapshot_into(&self, dst: &mut Self) {
+        dst.clone_from(self);
+    }
+
+    fn snapshot(&self) -> Snapshot<Self> {
+        Snapshot::new(self.clone())
+    }
+
+    fn restore(&mut self, snapshot: Snapshot<Self>) {
+        *self = snapshot.into_inner();
+    }
+
+    fn retain_within(&mut self, bounds: Range<T::Type>) {
+        self.retain(|elem| helpers::ranges_overlap(&elem.range(), &bounds));
+    }
+
+    fn add_ranges<F>(
+        &mut self,
+        ranges: &[Range<T::Type>],
+        make: F,
+        temp: &mut [u8],
+    ) -> Result<(), RangeError<T>>
+    where
+        F: Fn(Range<T::Type>) -> T,
+    {
+        self.merge_extend(ranges.iter().cloned().map(make), temp)
+    }
+
+    fn any_overlap(&self, ranges: &[Range<T::Type>]) -> bool {
+        ranges
+            .iter()
+            .any(|range| core_ops::overlaps_range(self.as_slice(), range))
+    }
+
+    fn merge_add_checked(
+        &mut self,
+        new_info: T,
+        strict: bool,
+        temp: &mut [u8],
+    ) -> Result<(), RangeError<T>> {
+        if strict && !core_ops::validate_range(&new_info) {
+            return Err(RangeError::EmptyRange);
+        }
+        self.merge_add(new_info, temp)
+    }
+
+    fn merge_add_strict(&mut self, new_info: T, temp: &mut [u8]) -> Result<(), RangeError<T>> {
+        self.merge_add_checked(new_info, true, temp)
+    }
+
+    fn merge_add_exclusive(&mut self, new_info: T, temp: &mut [u8]) -> Result<(), RangeError<T>> {
+        core_ops::check_exclusive(self.as_slice(), &new_info)?;
+        self.merge_add(new_info, temp)
+    }
+
+    fn merge_add_within(
+        &mut self,
+        new_info: T,
+        universe: Range<T::Type>,
+        temp: &mut [u8],
+    ) -> Result<(), RangeError<T>> {
+        core_ops::check_universe(&new_info, &universe)?;
+        self.merge_add(new_info, temp)
+    }
+
+    fn coverage_ratio(&self, bounds: Range<T::Type>) -> f64
+    where
+        T::Type: Coordinate,
+    {
+        core_ops::coverage_ratio(self.as_slice(), &bounds)
+    }
+
+    fn coalesce(&mut self) {
+        let len = core_ops::coalesce_in_place(self.as_mut_slice());
+        self.truncate(len);
+    }
+
+    fn coalesce_by<F>(&mut self, eq: F)
+    where
+        F: FnMut(&T::Kind, &T::Kind) -> bool,
+    {
+        let len = core_ops::coalesce_in_place_by(self.as_mut_slice(), eq);
+        self.truncate(len);
+    }
+
+    fn chunk_elements(&mut self, stride: T::Type, temp: &mut [u8]) -> Result<(), RangeError<T>>
+    where
+        T::Type: Add<Output = T::Type> + Sub<Output = T::Type>,
+    {
+        let temp_buff = bytes_to_slice_mut::<T>(temp);
+        let mut temp = SliceVec::from_slice_len(temp_buff, 0);
+        core_ops::chunk_elements(self.as_slice(), stride, &mut temp)?;
+        core_ops::copy_back(self, &temp)
+    }
+
+    fn pairs<'a>(&'a self) -> impl Iterator<Item = (&'a T, &'a T)>
+    where
+        T: 'a,
+    {
+        self.as_slice().windows(2).map(|pair| (&pair[0], &pair[1]))
+    }
+
+    fn kind_boundaries(&self) -> impl Iterator<Item = T::Type> {
+        self.pairs()
+            .filter(|(prev, next)| prev.kind() != next.kind())
+            .map(|(_, next)| next.range().start)
+    }
+
+    fn merged_iter(&self) -> impl Iterator<Item = Range<T::Type>> {
+        self.iter().map(|e| e.range())
+    }
+
+    fn originals_iter(&self) -> impl Iterator<Item = Range<T::Type>>
+    where
+        T: ProvenanceRangeInfo,
+    {
+        self.iter().flat_map(|e| e.originals())
+    }
+
+    fn shift_by(&mut self, delta: T::Type) -> Result<(), RangeError<T>>
+    where
+        T::Type: Coordinate,
+    {
+        core_ops::shift_by_checked(self.as_mut_slice(), delta)
+    }
+
+    fn shift_by_saturating(&mut self, delta: T::Type)
+    where
+        T::Type: Coordinate,
+    {
+        let len = core_ops::shift_by_saturating(self.as_mut_slice(), delta);
+        self.truncate(len);
+    }
+
+    fn shift_after(&mut self, pivot: T::Type, delta: T::Type) -> Result<(), RangeError<T>>
+    where
+        T::Type: Coordinate,
+    {
+        let can_split = !self.is_full();
+        if let Some((index, right)) =
+            core_ops::shift_after(self.as_mut_slice(), pivot, delta, can_split)?
+        {
+            VecOps::insert(self, index, right)?;
+        }
+        Ok(())
+    }
+
+    fn collapse(&mut self, range: Range<T::Type>)
+    where
+        T::Type: Coordinate,
+    {
+        let len = core_ops::collapse(self.as_mut_slice(), &range);
+        self.truncate(len);
+    }
+
+    fn add_to_end(
+        &mut self,
+        start: T::Type,
+        template: &T,
+        temp: &mut [u8],
+    ) -> Result<(), RangeError<T>>
+    where
+        T::Type: Coordinate,
+    {
+        self.merge_add(template.clone_with_range(start..T::Type::MAX), temp)
+    }
+
+    fn cursor(&self) -> Cursor<'_, T> {
+        Cursor::new(self.as_slice())
+    }
+
+    fn entry(&mut self, range: Range<T::Type>) -> Entry<'_, Self, T> {
+        Entry::new(self, range)
+    }
+
+    fn reclassify(
+        &mut self,
+        range: Range<T::Type>,
+        template: &T,
+        temp: &mut [u8],
+    ) -> Result<(), RangeError<T>> {
+        let backup = self.clone();
+        let result = self
+            .merge_remove(range.clone(), temp)
+            .and_then(|_| self.merge_add(template.clone_with_range(range), temp));
+        if result.is_err() {
+            *self = backup;
+        }
+
+        result
+    }
+
+    fn tombstone(
+        &mut self,
+        range: Range<T::Type>,
+        sentinel: T::Kind,
+        template: &T,
+        temp: &mut [u8],
+    ) -> Result<(), RangeError<T>> {
+        core_ops::check_template_kind(template, sentinel)?;
+        let covered: heapless::Vec<Range<T::Type>, N> =
+            core_ops::covered_within(self.as_slice(), range.clone()).collect();
+        let backup = self.clone();
+        let result = self.merge_remove(range, temp).and_then(|_| {
+            covered
+                .into_iter()
+                .try_for_each(|part| self.merge_add(template.clone_with_range(part), temp))
+        });
+        if result.is_err() {
+            *self = backup;
+        }
+
+        result
+    }
+
+    fn merge_with<F>(
+        &mut self,
+        other: &Self,
+        resolve: F,
+        temp: &mut [u8],
+    ) -> Result<(), RangeError<T>>
+    where
+        F: FnMut(&T, &T) -> T,
+    {
+        let temp_buff = bytes_to_slice_mut::<T>(temp);
+        let mut temp = SliceVec::from_slice_len(temp_buff, 0);
+        let backup = self.clone();
+        let result = core_ops::merge_with(self, other.as_slice(), resolve, &mut temp);
+        if result.is_err() {
+            *self = backup;
+        }
+
+        result
+    }
+
+    fn merge_add_priority(&mut self, new_info: T, temp: &mut [u8]) -> Result<(), RangeError<T>>
+    where
+        T: PriorityRangeInfo,
+    {
+        let temp_buff = bytes_to_slice_mut::<T>(temp);
+        let mut temp = SliceVec::from_slice_len(temp_buff, 0);
+        let backup = self.clone();
+        let result = core_ops::merge_with(
+            self,
+            core::slice::from_ref(&new_info),
+            core_ops::prefer_priority,
+            &mut temp,
+        );
+        if result.is_err() {
+            *self = backup;
+        }
+
+        result
+    }
+
+    fn set_overwritable(&mut self, range: Range<T::Type>, value: bool) -> Result<(), RangeError<T>>
+    where
+        T: LockableRangeInfo,
+    {
+        let spare = self.capacity() - self.len();
+        let pieces = core_ops::set_overwritable(self.as_mut_slice(), &range, value, spare)?;
+        for (index, piece) in pieces.into_iter().flatten() {
+            VecOps::insert(self, index, piece)?;
+        }
+        let len = core_ops::coalesce_in_place(self.as_mut_slice());
+        self.truncate(len);
+        Ok(())
+    }
+
+    fn element_count(&self) -> usize {
+        self.len()
+    }
+
+    fn total_len(&self) -> T::Type
+    where
+        T::Type: Add<Output = T::Type> + Sub<Output = T::Type> + Default,
+    {
+        self.iter().fold(T::Type::default(), |total, elem| {
+            total + (elem.range().end - elem.range().start)
+        })
+    }
+
+    fn covered_bytes(&self) -> u64
+    where
+        T::Type: Coordinate,
+    {
+        core_ops::covered_bytes(self.as_slice())
+    }
 }
 
 impl<T: RangeInfo, const N: usize> VecOps<T> for heapless::Vec<T, N> {
@@ -62,6 +777,10 @@ impl<T: RangeInfo, const N: usize> VecOps<T> for heapless::Vec<T, N> {
         self.as_slice().len()
     }
 
+    fn capacity(&self) -> usize {
+        N
+    }
+
     fn remove(&mut self, index: usize) -> T {
         self.remove(index)
     }
@@ -101,6 +820,10 @@ impl<T: RangeInfo> VecOps<T> for SliceVec<'_, T> {
         self.as_slice().len()
     }
 
+    fn capacity(&self) -> usize {
+        self.capacity()
+    }
+
     fn remove(&mut self, index: usize) -> T {
         self.remove(index)
     }
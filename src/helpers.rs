@@ -1,6 +1,6 @@
-use core::{mem, ops::Range, slice};
+use core::{cmp::Ordering, mem, ops::Range, slice};
 
-use crate::RangeInfo;
+use crate::{Coordinate, RangeInfo};
 
 /// 检查两个区间是否有交集
 #[inline]
@@ -8,7 +8,40 @@ pub fn ranges_overlap<T1: Ord + Copy>(r1: &Range<T1>, r2: &Range<T1>) -> bool {
     !(r1.end <= r2.start || r1.start >= r2.end)
 }
 
-/// 分割区间：将原区间按分割范围分割成不重叠的部分
+/// 饱和加法：溢出时停在类型上界
+#[inline]
+pub fn saturating_add<R: Coordinate>(a: R, b: R) -> R {
+    a.saturating_add(b)
+}
+
+/// 饱和减法：溢出时停在类型下界
+#[inline]
+pub fn saturating_sub<R: Coordinate>(a: R, b: R) -> R {
+    a.saturating_sub(b)
+}
+
+/// 构造区间，`start >= end`（空区间或反向区间）时返回 `None`
+#[inline]
+pub fn checked_range<R: Ord>(start: R, end: R) -> Option<Range<R>> {
+    (start < end).then_some(start..end)
+}
+
+/// 按区间起点比较两个元素，与集合内部的排序方式一致
+///
+/// 可用于 `slice::sort_by(cmp_by_start)`。
+#[inline]
+pub fn cmp_by_start<T: RangeInfo>(a: &T, b: &T) -> Ordering {
+    a.range().start.cmp(&b.range().start)
+}
+
+/// 分割区间：将原区间按分割范围分割成不重叠的部分，也是 `RangeInfo::split` 的默认实现
+///
+/// 返回 `[左侧剩余, 右侧剩余]`，为空的一侧为 `None`：
+///
+/// - 分割范围位于元素内部：`[Some, Some]`
+/// - 分割范围只覆盖元素的右端或左端：`[Some, None]` / `[None, Some]`
+/// - 分割范围覆盖整个元素（包括与元素完全相等）：`[None, None]`，即元素被整体移除，
+///   调用方不能假设至少有一侧为 `Some`
 pub fn split_range<T: RangeInfo>(elem: &T, split_range: &Range<T::Type>) -> [Option<T>; 2] {
     let elem_range = elem.range();
     let has_left = elem_range.start < split_range.start;
@@ -0,0 +1,106 @@
+use core::ops::Range;
+
+use crate::{RangeError, RangeInfo};
+
+/// 分层区间集合：不同 kind 的区间可以重叠共存
+///
+/// 与 [`RangeVecOps::merge_add`](crate::RangeVecOps::merge_add) 的覆盖语义不同，
+/// `add` 从不移除已有覆盖：同 kind 的重叠或相邻区间合并，不同 kind 之间互不影响，
+/// `overwritable` 在此模式下不起作用。元素按起点排序。
+#[derive(Clone, Debug)]
+pub struct LayeredRangeSet<T: RangeInfo, const N: usize> {
+    items: heapless::Vec<T, N>,
+}
+
+impl<T: RangeInfo, const N: usize> Default for LayeredRangeSet<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: RangeInfo, const N: usize> LayeredRangeSet<T, N> {
+    pub const fn new() -> Self {
+        Self {
+            items: heapless::Vec::new(),
+        }
+    }
+
+    /// 添加一个区间，与同 kind 的重叠或相邻区间合并
+    ///
+    /// 容量不足时返回 `Capacity`，集合保持不变。
+    pub fn add(&mut self, info: T) -> Result<(), RangeError<T>> {
+        let range = info.range();
+        if range.start >= range.end {
+            return Ok(());
+        }
+
+        let kind = info.kind();
+        let touches = |e: &T| {
+            let r = e.range();
+            e.kind() == kind && r.start <= range.end && r.end >= range.start
+        };
+
+        let mut merged = range.clone();
+        let mut touched = 0;
+        for e in self.items.iter().filter(|e| touches(e)) {
+            let r = e.range();
+            merged.start = core::cmp::min(merged.start, r.start);
+            merged.end = core::cmp::max(merged.end, r.end);
+            touched += 1;
+        }
+
+        if touched == 0 && self.items.is_full() {
+            return Err(RangeError::Capacity);
+        }
+
+        let mut info = if merged == range {
+            info
+        } else {
+            info.clone_with_range(merged.clone())
+        };
+        for e in self.items.iter().filter(|e| touches(e)) {
+            info.merge_meta(e);
+        }
+
+        self.items.retain(|e| !touches(e));
+        let pos = self
+            .items
+            .partition_point(|e| e.range().start <= merged.start);
+        self.items
+            .insert(pos, info)
+            .map_err(|_| RangeError::Capacity)
+    }
+
+    /// 返回覆盖 `value` 的所有区间的 kind，按区间起点排序
+    pub fn kinds_at(&self, value: T::Type) -> impl Iterator<Item = T::Kind> + '_ {
+        self.covering(value).map(|e| e.kind())
+    }
+
+    /// 返回覆盖 `value` 的所有区间
+    pub fn covering(&self, value: T::Type) -> impl Iterator<Item = &T> + '_ {
+        let end = self.items.partition_point(|e| e.range().start <= value);
+        self.items[..end]
+            .iter()
+            .filter(move |e| value < e.range().end)
+    }
+
+    /// 返回与 `range` 有交集的所有区间
+    pub fn overlapping(&self, range: Range<T::Type>) -> impl Iterator<Item = &T> + '_ {
+        let end = self.items.partition_point(|e| e.range().start < range.end);
+        self.items[..end]
+            .iter()
+            .filter(move |e| range.start < e.range().end)
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        &self.items
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
@@ -6,17 +6,55 @@ extern crate alloc;
 use core::{
     cmp::{max, min},
     fmt::Debug,
-    ops::Range,
+    ops::{Add, Range, Sub},
 };
 
+mod builder;
 pub(crate) mod core_ops;
+mod cursor;
+mod entry;
 mod heapless_ops;
 pub(crate) mod helpers;
+mod layered;
+mod observer;
+mod ordered;
 pub mod prelude;
+mod snapshot;
+mod unit_range;
 
 #[cfg(feature = "alloc")]
 mod alloc_ops;
 
+pub use builder::RangeSetBuilder;
+pub use core_ops::{find_insert_position, find_insert_position_stable};
+pub use cursor::Cursor;
+pub use entry::{Entry, VacantEntry};
+pub use helpers::{checked_range, cmp_by_start, saturating_add, saturating_sub, split_range};
+pub use layered::LayeredRangeSet;
+use observer::SplitHook;
+pub use observer::{MergeObserver, NoopObserver};
+pub use ordered::{OrdF32, OrdF64};
+pub use snapshot::Snapshot;
+pub use unit_range::UnitRange;
+
+/// `RangeSet` 未指定容量时的默认容量
+pub const DEFAULT_CAPACITY: usize = 16;
+
+/// 固定容量的区间集合（heapless 模式），`N` 默认为 [`DEFAULT_CAPACITY`]
+///
+/// 元素直接存放在 `heapless::Vec` 中，占用 `N * size_of::<T>()` 的空间；
+/// 需要按实际用量确定大小时请显式指定 `N`。
+///
+/// `RangeSet::new()` 是 `const fn`，可以直接用于 `static` 的初始化。
+///
+/// 只删除元素的 `Vec` 固有方法（如 `truncate`、`clear`）不会破坏集合不变量，可以直接使用；
+/// 例如 `truncate(len)` 只保留坐标最低的 `len` 个元素。
+pub type RangeSet<T, const N: usize = DEFAULT_CAPACITY> = heapless::Vec<T, N>;
+
+/// 容量可增长的区间集合（alloc 模式），不需要指定容量
+#[cfg(feature = "alloc")]
+pub type RangeSetVec<T> = alloc::vec::Vec<T>;
+
 pub trait VecOps<T: RangeInfo> {
     fn push(&mut self, item: T) -> Result<(), RangeError<T>>;
     fn as_slice(&self) -> &[T];
@@ -27,113 +65,179 @@ pub trait VecOps<T: RangeInfo> {
     fn is_empty(&self) -> bool {
         self.len() == 0
     }
+    /// 容器最多能容纳的元素数量，可增长的容器返回 `usize::MAX`
+    fn capacity(&self) -> usize {
+        usize::MAX
+    }
     fn remove(&mut self, index: usize) -> T;
     fn insert(&mut self, index: usize, item: T) -> Result<(), RangeError<T>>;
     fn clear(&mut self);
+    /// 预留至少还能容纳 `additional` 个元素的空间，之后的 `push` 不会因分配失败而出错；
+    /// 固定容量的容器什么都不做
+    fn reserve_items(&mut self, _additional: usize) -> Result<(), RangeError<T>> {
+        Ok(())
+    }
 }
 
 pub trait RangeExtBaseOps<T: RangeInfo>: VecOps<T> {
+    /// 添加一个区间；所有计算都在 `temp` 中完成，失败时 `self` 保持不变
     fn merge_add_with_temp(
         &mut self,
         new_info: T,
         temp: &mut impl VecOps<T>,
     ) -> Result<(), RangeError<T>> {
+        self.merge_add_with_temp_and_split_hook(new_info, temp, |_| {})?;
+        Ok(())
+    }
+
+    /// 同 `merge_add_with_temp`，但被新区间分割后保留下来的每个片段都会先传给 `on_split`
+    ///
+    /// 成功时返回新区间最终所在元素（合并后）的区间；空区间不做任何事，原样返回。
+    fn merge_add_with_temp_and_split_hook(
+        &mut self,
+        new_info: T,
+        temp: &mut impl VecOps<T>,
+        on_split: impl FnMut(&mut T),
+    ) -> Result<Range<T::Type>, RangeError<T>> {
+        self.merge_add_with_observer(new_info, temp, &mut SplitHook(on_split))
+    }
+
+    /// 同 `merge_add_with_temp_and_split_hook`，合并、分割与冲突都会通知 `observer`
+    fn merge_add_with_observer(
+        &mut self,
+        new_info: T,
+        temp: &mut impl VecOps<T>,
+        observer: &mut impl MergeObserver<T>,
+    ) -> Result<Range<T::Type>, RangeError<T>> {
         temp.clear();
         if !core_ops::validate_range(&new_info) {
-            return Ok(());
+            return Ok(new_info.range());
         }
 
         // 检查冲突
-        core_ops::check_conflicts(self.as_slice(), &new_info)?;
+        if let Err(e) = core_ops::check_conflicts(self.as_slice(), &new_info) {
+            if let RangeError::Conflict { new, existing } = &e {
+                observer.on_conflict(new, existing);
+            }
+            return Err(e);
+        }
 
-        for elem in self.drain(..) {
+        for elem in self.as_slice() {
             if !helpers::ranges_overlap(&elem.range(), &new_info.range()) {
-                temp.push(elem)?;
+                temp.push(elem.clone())?;
                 continue;
             }
 
             if elem.kind() == new_info.kind() {
-                temp.push(elem)?;
+                temp.push(elem.clone())?;
                 continue;
             }
 
-            let split_parts = helpers::split_range(&elem, &new_info.range());
-            for part in split_parts.iter().flatten() {
-                temp.push(part.clone())?;
+            // split 不会产生空片段；区间完全相同时结果为 [None, None]，旧元素被整体替换
+            let split_parts = elem.split(&new_info.range());
+            for mut part in split_parts.into_iter().flatten() {
+                observer.on_split(&mut part);
+                temp.push(part)?;
             }
         }
 
-        // 将处理后的结果复制回原数组（正序）
-        for elem in temp.as_slice() {
-            self.push(elem.clone())?;
-        }
-
         // 插入新区间并合并
-        if self.is_empty() {
-            self.push(new_info).map_err(|_| RangeError::Capacity)?;
-            return Ok(());
+        if temp.is_empty() {
+            let range = new_info.range();
+            temp.push(new_info)?;
+            core_ops::copy_back(self, temp)?;
+            return Ok(range);
         }
 
-        // 二分查找插入位置
-        let insert_at = core_ops::find_insert_position(self.as_slice(), &new_info.range());
+        let new_range = new_info.range();
+        let new_kind = new_info.kind();
 
-        let mut merged_range = new_info.range();
-        let mut insert_at = insert_at;
+        // 二分查找插入位置
+        let insert_at = core_ops::find_insert_position(temp.as_slice(), &new_range);
+        let mut merged_range = new_range.clone();
+        let overwritable = core_ops::merged_overwritable(temp.as_slice(), &new_info);
 
-        // 向左合并
-        while insert_at > 0 {
-            let left = &self.as_slice()[insert_at - 1];
-            if left.range().end < merged_range.start || left.kind() != new_info.kind() {
+        // 向左合并：只计算边界，最后一次性删除；相接而 overwritable 不同的元素保持独立
+        let mut lo = insert_at;
+        while lo > 0 {
+            let left = &temp.as_slice()[lo - 1];
+            let left_range = left.range();
+            if left.kind() != new_kind || !core_ops::absorbs(left, &merged_range, overwritable) {
                 break;
             }
-            merged_range.start = min(merged_range.start, left.range().start);
-            merged_range.end = max(merged_range.end, left.range().end);
-            self.remove(insert_at - 1);
-            insert_at -= 1;
+            merged_range.start = min(merged_range.start, left_range.start);
+            merged_range.end = max(merged_range.end, left_range.end);
+            lo -= 1;
         }
 
         // 向右合并
-        while insert_at < self.len() {
-            let right = &self.as_slice()[insert_at];
-            if right.range().start > merged_range.end || right.kind() != new_info.kind() {
+        let mut hi = insert_at;
+        while hi < temp.len() {
+            let right = &temp.as_slice()[hi];
+            let right_range = right.range();
+            if right.kind() != new_kind || !core_ops::absorbs(right, &merged_range, overwritable) {
                 break;
             }
-            merged_range.start = min(merged_range.start, right.range().start);
-            merged_range.end = max(merged_range.end, right.range().end);
-            self.remove(insert_at);
+            merged_range.start = min(merged_range.start, right_range.start);
+            merged_range.end = max(merged_range.end, right_range.end);
+            hi += 1;
         }
 
-        self.insert(insert_at, new_info.clone_with_range(merged_range))?;
-        Ok(())
+        // 区间没有扩展时直接插入新元素，否则只构造一次合并后的元素
+        let merged = core_ops::build_merged(
+            new_info,
+            merged_range.clone(),
+            temp.as_slice()[lo..hi].iter(),
+        );
+        temp.drain(lo..hi).for_each(drop);
+        if hi > lo {
+            observer.on_merge(&merged);
+        }
+        temp.insert(lo, merged)?;
+        core_ops::copy_back(self, temp)?;
+        debug_assert_eq!(
+            core_ops::validate_window(self.as_slice(), &merged_range),
+            Ok(())
+        );
+        Ok(merged_range)
     }
 
+    /// 删除一个区间；所有计算都在 `temp` 中完成，失败时 `self` 保持不变
     fn merge_remove_with_temp(
         &mut self,
         range: Range<T::Type>,
         temp: &mut impl VecOps<T>,
+    ) -> Result<(), RangeError<T>> {
+        self.merge_remove_with_observer(range, temp, &mut NoopObserver)
+    }
+
+    /// 同 `merge_remove_with_temp`，被切开的元素保留下来的片段会通知 `observer`
+    fn merge_remove_with_observer(
+        &mut self,
+        range: Range<T::Type>,
+        temp: &mut impl VecOps<T>,
+        observer: &mut impl MergeObserver<T>,
     ) -> Result<(), RangeError<T>> {
         temp.clear();
         if range.start >= range.end || self.is_empty() {
             return Ok(());
         }
 
-        for elem in self.drain(..) {
+        for elem in self.as_slice() {
             if !helpers::ranges_overlap(&elem.range(), &range) {
-                temp.push(elem)?;
+                temp.push(elem.clone())?;
                 continue;
             }
 
-            let split_parts = helpers::split_range(&elem, &range);
-            for part in split_parts.iter().flatten() {
-                temp.push(part.clone())?;
+            let split_parts = elem.split(&range);
+            for mut part in split_parts.into_iter().flatten() {
+                observer.on_split(&mut part);
+                temp.push(part)?;
             }
         }
 
-        // 将处理后的结果复制回原数组（正序）
-        for elem in temp.as_slice() {
-            self.push(elem.clone())?;
-        }
-
+        core_ops::copy_back(self, temp)?;
+        debug_assert_eq!(core_ops::validate_window(self.as_slice(), &range), Ok(()));
         Ok(())
     }
 }
@@ -141,14 +245,509 @@ pub trait RangeExtBaseOps<T: RangeInfo>: VecOps<T> {
 pub trait RangeVecOps<T: RangeInfo> {
     fn merge_add(&mut self, new_info: T, temp: &mut [u8]) -> Result<(), RangeError<T>>;
 
+    /// 同 `merge_add`，成功时返回新区间最终所在元素（与邻居合并后）的区间
+    fn add_and_report(
+        &mut self,
+        new_info: T,
+        temp: &mut [u8],
+    ) -> Result<Range<T::Type>, RangeError<T>>;
+
     fn merge_remove(&mut self, range: Range<T::Type>, temp: &mut [u8])
     -> Result<(), RangeError<T>>;
 
+    /// 同 `merge_remove`，并返回删除对元素数量的影响
+    fn remove_with_stats(
+        &mut self,
+        range: Range<T::Type>,
+        temp: &mut [u8],
+    ) -> Result<RemoveStats, RangeError<T>>;
+
+    /// 删除区间，但 `range` 覆盖到不可覆盖的元素时返回 `Conflict`，集合保持不变
+    ///
+    /// 不检查保护的删除请使用 `merge_remove`。
+    fn try_merge_remove(
+        &mut self,
+        range: Range<T::Type>,
+        temp: &mut [u8],
+    ) -> Result<(), RangeError<T>>;
+
+    /// 批量删除多个区间：排序后一次扫描集合，结果与逐个 `merge_remove` 相同
+    ///
+    /// 区间按容量 `N` 分批处理，每批在栈上排序。失败时恢复原状态。
+    fn merge_remove_many<I>(&mut self, ranges: I, temp: &mut [u8]) -> Result<(), RangeError<T>>
+    where
+        I: IntoIterator<Item = Range<T::Type>>;
+
+    /// 同 `merge_add`，过程中的合并、分割与冲突会通知 `observer`
+    fn merge_add_observed(
+        &mut self,
+        new_info: T,
+        observer: &mut impl MergeObserver<T>,
+        temp: &mut [u8],
+    ) -> Result<(), RangeError<T>>;
+
+    /// 同 `merge_remove`，被切开的元素保留下来的片段会通知 `observer`
+    fn merge_remove_observed(
+        &mut self,
+        range: Range<T::Type>,
+        observer: &mut impl MergeObserver<T>,
+        temp: &mut [u8],
+    ) -> Result<(), RangeError<T>>;
+
     fn merge_extend<I>(&mut self, ranges: I, temp: &mut [u8]) -> Result<(), RangeError<T>>
     where
         I: IntoIterator<Item = T>;
 
+    /// 批量添加多个区间，成功时返回添加的数量；失败时返回失败前已添加的数量和错误
+    fn merge_extend_counted<I>(
+        &mut self,
+        ranges: I,
+        temp: &mut [u8],
+    ) -> Result<usize, (usize, RangeError<T>)>
+    where
+        I: IntoIterator<Item = T>;
+
+    /// 批量添加多个区间，任一区间失败时恢复到调用前的状态
+    fn merge_extend_atomic<I>(&mut self, ranges: I, temp: &mut [u8]) -> Result<(), RangeError<T>>
+    where
+        I: IntoIterator<Item = T>;
+
+    /// 查询某个值是否落在任意一个区间中
+    ///
+    /// 区间是半开的 `[start, end)`：以 `T::Type::MAX` 为终点表示“到无穷”时，`MAX` 本身永远不被包含。
     fn contains_point(&self, value: T::Type) -> bool;
+
+    /// 返回覆盖 `value` 的元素下标，可直接用于索引，避免再次查找
+    fn point_index(&self, value: T::Type) -> Option<usize>;
+
+    /// 查询 `range` 是否有任意部分被覆盖（部分重叠即可），`range` 为空时返回 false
+    ///
+    /// ```rust
+    /// use ranges_ext::{RangeVecOps, UnitRange};
+    ///
+    /// let mut set = heapless::Vec::<UnitRange<u32>, 4>::new();
+    /// let mut temp = [0u8; 256];
+    /// set.merge_add(UnitRange::new(0..10), &mut temp).unwrap();
+    ///
+    /// // 部分重叠即可
+    /// assert!(set.contains_any(5..15));
+    /// assert!(!set.contains_any(10..15));
+    /// ```
+    fn contains_any(&self, range: Range<T::Type>) -> bool;
+
+    /// 查询 `range` 是否被完全覆盖（可以跨越多个首尾相接的元素），`range` 为空时返回 true
+    ///
+    /// ```rust
+    /// use ranges_ext::{RangeVecOps, UnitRange};
+    ///
+    /// let mut set = heapless::Vec::<UnitRange<u32>, 4>::new();
+    /// let mut temp = [0u8; 256];
+    /// set.merge_add(UnitRange::new(0..10), &mut temp).unwrap();
+    ///
+    /// // 部分覆盖的区间不算被包含
+    /// assert!(set.contains_all(2..8));
+    /// assert!(!set.contains_all(5..15));
+    /// ```
+    fn contains_all(&self, range: Range<T::Type>) -> bool;
+
+    /// 检查集合内部不变量：有序、不重叠、非空，且相邻同 kind、同 `overwritable` 的区间已合并
+    fn validate(&self) -> Result<(), InvariantError>;
+
+    /// 预测执行 merge_add 后集合中的元素数量（不修改集合，不检查冲突）
+    fn merge_add_predicted_len(&self, info: &T) -> usize;
+
+    /// 判断执行 merge_add 过程中是否会超出容量（不修改集合，不检查冲突）
+    fn would_fit(&self, info: &T) -> bool;
+
+    /// 删除所有 kind 等于给定值的区间，保留其余区间（不会重新合并）
+    fn remove_by_kind(&mut self, kind: &T::Kind);
+
+    /// 删除所有满足谓词的区间，保留其余区间（不会重新合并）
+    fn remove_if<F>(&mut self, f: F)
+    where
+        F: FnMut(&T) -> bool;
+
+    /// 删除并返回坐标最低的元素；需要移动其余元素，O(n)
+    fn pop_first(&mut self) -> Option<T>;
+
+    /// 删除并返回坐标最高的元素，O(1)
+    fn pop_last(&mut self) -> Option<T>;
+
+    /// 删除长度小于 `min_len` 的元素，留下空隙（不会重新合并）
+    fn drop_smaller_than(&mut self, min_len: T::Type)
+    where
+        T::Type: Coordinate;
+
+    /// 依次返回每个元素的 (区间, kind)
+    ///
+    /// `RangeInfo::kind` 按值返回，这里的 kind 同样是克隆出的值。
+    fn ranges_with_kind(&self) -> impl Iterator<Item = (Range<T::Type>, T::Kind)>;
+
+    /// 按连续相同的 kind 分组统计，返回每组的 (kind, 元素数量, 覆盖长度)
+    fn count_by_kind(&self) -> impl Iterator<Item = (T::Kind, usize, T::Type)>
+    where
+        T::Type: Sub<Output = T::Type> + Add<Output = T::Type>;
+
+    /// 将所有元素克隆到 `out` 中（会先清空 `out`），容量不足时返回 `Capacity` 且不修改 `out`
+    fn collect_into<const M: usize>(
+        &self,
+        out: &mut heapless::Vec<T, M>,
+    ) -> Result<(), RangeError<T>>;
+
+    /// 只收集每个元素的 `[start, end)` 区间，不克隆元素，容量不足时返回 `Capacity`
+    fn copied_ranges<const M: usize>(
+        &self,
+    ) -> Result<heapless::Vec<Range<T::Type>, M>, RangeError<T>>;
+
+    /// 转移到容量为 `M` 的集合中，`len() > M` 时原样返回 `self`
+    ///
+    /// 可用于把临时构造的大集合压缩成按实际元素数量确定大小的长期存储。
+    fn try_into_capacity<const M: usize>(self) -> Result<RangeSet<T, M>, Self>
+    where
+        Self: Sized;
+
+    /// 以切片形式借用内部元素，不发生拷贝；任何修改操作都会结束该借用
+    fn as_element_slice(&self) -> &[T];
+
+    /// 以切片形式借用与 bounds 重叠的所有元素（元素有序，重叠的元素总是连续的），
+    /// 首尾元素可能超出 bounds；bounds 为空或没有重叠时返回空切片
+    fn elements_slice(&self, bounds: Range<T::Type>) -> &[T];
+
+    /// 快速追加已排序且互不重叠的区间，每个区间只与当前末尾元素合并
+    ///
+    /// 调用方需保证每个区间的起点不小于前一个区间（以及集合末尾元素）的终点，
+    /// debug 模式下会断言该前提。出错时已追加的区间会保留。
+    fn extend_sorted_disjoint<I>(&mut self, ranges: I) -> Result<(), RangeError<T>>
+    where
+        I: IntoIterator<Item = T>;
+
+    /// 查询区间内的每个点是否都被 kind 等于给定值的元素覆盖
+    fn covered_by_kind(&self, range: Range<T::Type>, kind: &T::Kind) -> bool;
+
+    /// 返回 bounds 内未被任何元素覆盖的空隙（按顺序）
+    ///
+    /// 空隙已截断到 bounds 内，即查询区间中尚未覆盖的部分，例如缓存中需要补取的范围。
+    fn gaps_within(&self, bounds: Range<T::Type>) -> impl Iterator<Item = Range<T::Type>>;
+
+    /// 返回 query 中未被任何元素覆盖的部分（按顺序），与 `gaps_within(query)` 相同
+    fn uncovered_within(&self, query: Range<T::Type>) -> impl Iterator<Item = Range<T::Type>>;
+
+    /// 返回 range 内第一个未被覆盖的坐标，完全覆盖（或 range 为空）时返回 `None`
+    fn first_uncovered(&self, range: Range<T::Type>) -> Option<T::Type>;
+
+    /// 首次适配：返回 bounds 内第一个能容纳 size 的空隙中、从其起点开始长度为 size 的区间
+    fn first_fit(&self, size: T::Type, bounds: Range<T::Type>) -> Option<Range<T::Type>>
+    where
+        T::Type: Add<Output = T::Type> + Sub<Output = T::Type>;
+
+    /// 最佳适配：返回 bounds 内能容纳 size 的最小空隙中、从其起点开始长度为 size 的区间
+    fn best_fit(&self, size: T::Type, bounds: Range<T::Type>) -> Option<Range<T::Type>>
+    where
+        T::Type: Add<Output = T::Type> + Sub<Output = T::Type>;
+
+    /// 在 bounds 内按首次适配分配长度为 size 的区间，用 `make` 构造元素并插入集合
+    ///
+    /// 没有合适空隙时返回 `RangeError::NoFit`。
+    fn allocate<F>(
+        &mut self,
+        size: T::Type,
+        bounds: Range<T::Type>,
+        make: F,
+        temp: &mut [u8],
+    ) -> Result<Range<T::Type>, RangeError<T>>
+    where
+        T::Type: Add<Output = T::Type> + Sub<Output = T::Type>,
+        F: FnOnce(Range<T::Type>) -> T;
+
+    /// 严格删除：range 中任何部分未被覆盖时返回 `RangeError::NotAllocated` 且不修改集合
+    fn deallocate(&mut self, range: Range<T::Type>, temp: &mut [u8]) -> Result<(), RangeError<T>>;
+
+    /// 调整与 `old` 完全相同的元素的终点：增长时按 merge_add 规则合并或报告冲突，缩小时截断尾部
+    ///
+    /// 找不到与 `old` 完全相同的元素时返回 `RangeError::NotAllocated`；`new_end` 不大于 `old.start`
+    /// （缩小为空区间）时返回 `RangeError::EmptyRange`，删除整个元素请使用 `merge_remove`。
+    fn resize_element(
+        &mut self,
+        old: Range<T::Type>,
+        new_end: T::Type,
+        temp: &mut [u8],
+    ) -> Result<(), RangeError<T>>;
+
+    /// 将 `from` 覆盖的内容（保留 kind）移动到以 `to_start` 为起点的等长区间
+    ///
+    /// `from` 未被完全覆盖时返回 `RangeError::NotAllocated`；目标位置冲突时返回错误并恢复原状态。
+    fn relocate(
+        &mut self,
+        from: Range<T::Type>,
+        to_start: T::Type,
+        temp: &mut [u8],
+    ) -> Result<(), RangeError<T>>
+    where
+        T::Type: Add<Output = T::Type> + Sub<Output = T::Type>;
+
+    /// 预览 merge_add 的结果：将 `self` 复制到 `out` 后在 `out` 上执行添加，`self` 保持不变
+    ///
+    /// 需要复制整个集合。
+    fn preview_add(&self, info: T, out: &mut Self, temp: &mut [u8]) -> Result<(), RangeError<T>>;
+
+    /// 按顺序遍历 bounds 内的已覆盖元素和空隙，包括首尾相对 bounds 的空隙
+    fn segments<'a>(&'a self, bounds: Range<T::Type>) -> impl Iterator<Item = Segment<'a, T>>
+    where
+        T: 'a;
+
+    /// 按顺序比较两个集合的覆盖情况：把至少一侧覆盖的部分切成片段，
+    /// 每个片段标明只被左侧、只被右侧还是被两侧覆盖，以及对应的 kind
+    ///
+    /// 任一侧的元素边界都会切开片段；两侧都未覆盖的空隙不产生片段。
+    fn relate<'a>(&'a self, other: &'a Self) -> impl Iterator<Item = Relation<T>>
+    where
+        T: 'a;
+
+    /// 判断两个集合是否没有公共覆盖点，只比较区间几何，不考虑 kind
+    fn is_disjoint_from(&self, other: &Self) -> bool;
+
+    /// 判断本集合的每个覆盖点是否都被 `other` 覆盖，只比较区间几何，不考虑 kind
+    fn is_subset_of(&self, other: &Self) -> bool;
+
+    /// 判断本集合的每个覆盖点是否都被 `other` 中 kind 相同的元素覆盖
+    fn is_subset_of_with_kind(&self, other: &Self) -> bool;
+
+    /// 添加一个区间，被分割的已有元素保留下来的每个片段都会先传给 `on_split` 调整元数据
+    ///
+    /// `merge_add` 保持片段原有的元数据不变。
+    fn merge_add_with_split_hook<F>(
+        &mut self,
+        new_info: T,
+        on_split: F,
+        temp: &mut [u8],
+    ) -> Result<(), RangeError<T>>
+    where
+        F: FnMut(&mut T);
+
+    /// 批量添加共享同一元数据的多个区间，元数据取自 `template`
+    ///
+    /// 输入每 N 个一批排序并合并重叠或相接的区间，再一次扫描并入集合；
+    /// 出错时集合保持调用前的状态。
+    fn add_many_same_kind<I>(
+        &mut self,
+        ranges: I,
+        template: &T,
+        temp: &mut [u8],
+    ) -> Result<(), RangeError<T>>
+    where
+        I: IntoIterator<Item = Range<T::Type>>;
+
+    /// 将所有元素复制到 `dst` 中并覆盖其原有内容
+    ///
+    /// 容量相同，不会失败；复制到不同容量的容器请使用 `collect_into`。
+    fn snapshot_into(&self, dst: &mut Self);
+
+    /// 保存当前状态，之后可通过 `restore` 撤销期间的所有修改
+    fn snapshot(&self) -> Snapshot<Self>
+    where
+        Self: Sized;
+
+    /// 将集合恢复为 `snapshot` 保存时的状态
+    fn restore(&mut self, snapshot: Snapshot<Self>)
+    where
+        Self: Sized;
+
+    /// 删除与 bounds 没有交集的元素，与 bounds 部分重叠的元素原样保留（不截断）
+    fn retain_within(&mut self, bounds: Range<T::Type>);
+
+    /// 批量添加裸区间，每个区间通过 `make` 构造成元素后按 merge_add 规则插入
+    fn add_ranges<F>(
+        &mut self,
+        ranges: &[Range<T::Type>],
+        make: F,
+        temp: &mut [u8],
+    ) -> Result<(), RangeError<T>>
+    where
+        F: Fn(Range<T::Type>) -> T;
+
+    /// 查询给定区间中是否有任意一个与已有覆盖重叠，命中后立即返回
+    fn any_overlap(&self, ranges: &[Range<T::Type>]) -> bool;
+
+    /// 添加一个区间；`strict` 为 true 时空区间返回 `RangeError::EmptyRange`，否则与 merge_add 一样忽略
+    fn merge_add_checked(
+        &mut self,
+        new_info: T,
+        strict: bool,
+        temp: &mut [u8],
+    ) -> Result<(), RangeError<T>>;
+
+    /// 严格添加一个区间：空区间返回 `RangeError::EmptyRange` 而不是被忽略
+    fn merge_add_strict(&mut self, new_info: T, temp: &mut [u8]) -> Result<(), RangeError<T>>;
+
+    /// 添加一个区间；新区间不可覆盖时只能落在空隙中，与任意已有元素（无论 kind 与是否可覆盖）
+    /// 重叠都返回 `Conflict`。可覆盖的新区间与 merge_add 相同。
+    fn merge_add_exclusive(&mut self, new_info: T, temp: &mut [u8]) -> Result<(), RangeError<T>>;
+
+    /// 同 `merge_add`，但区间部分或全部落在 `universe` 之外时返回 `OutOfBounds` 且不修改集合
+    ///
+    /// 空区间与 merge_add 一样被忽略，不做检查。
+    fn merge_add_within(
+        &mut self,
+        new_info: T,
+        universe: Range<T::Type>,
+        temp: &mut [u8],
+    ) -> Result<(), RangeError<T>>;
+
+    /// 计算 bounds 内被覆盖部分所占的比例（0.0 ~ 1.0），bounds 为空时返回 0.0
+    fn coverage_ratio(&self, bounds: Range<T::Type>) -> f64
+    where
+        T::Type: Coordinate;
+
+    /// 合并首尾相接的同 kind 相邻元素，恢复 `chunk_elements` 等操作之前的合并状态
+    fn coalesce(&mut self);
+
+    /// 合并首尾相接且 kind 满足 `eq` 的相邻元素，合并结果沿用左侧元素的 kind
+    fn coalesce_by<F>(&mut self, eq: F)
+    where
+        F: FnMut(&T::Kind, &T::Kind) -> bool;
+
+    /// 将长度超过 `stride` 的元素切分为若干长度不超过 `stride` 的连续片段，覆盖范围和 kind 不变
+    ///
+    /// 切分后相邻的同 kind 元素首尾相接，不再满足合并不变量（`validate` 报告 `Unmerged`），
+    /// 之后的添加与删除照常可用；可用 `coalesce` 恢复。
+    /// 容量不足时返回 `Capacity` 且不修改集合。
+    fn chunk_elements(&mut self, stride: T::Type, temp: &mut [u8]) -> Result<(), RangeError<T>>
+    where
+        T::Type: Add<Output = T::Type> + Sub<Output = T::Type>;
+
+    /// 按顺序返回每一对相邻元素
+    fn pairs<'a>(&'a self) -> impl Iterator<Item = (&'a T, &'a T)>
+    where
+        T: 'a;
+
+    /// 返回相邻元素 kind 发生变化的位置，即后一个元素的起点
+    ///
+    /// 无论两个元素首尾相接还是中间隔着空隙都算作一次变化；kind 相同的元素之间即使有空隙也不算。
+    fn kind_boundaries(&self) -> impl Iterator<Item = T::Type>;
+
+    /// 按顺序返回每个元素（合并后）的区间，与元素是否保留原始子区间无关
+    fn merged_iter(&self) -> impl Iterator<Item = Range<T::Type>>;
+
+    /// 按元素顺序展开每个元素保留的原始子区间
+    fn originals_iter(&self) -> impl Iterator<Item = Range<T::Type>>
+    where
+        T: ProvenanceRangeInfo;
+
+    /// 将所有元素向后平移 `delta`；任一坐标溢出时返回 `RangeError::Overflow` 且不修改集合
+    fn shift_by(&mut self, delta: T::Type) -> Result<(), RangeError<T>>
+    where
+        T::Type: Coordinate;
+
+    /// 将所有元素向后平移 `delta`，坐标在类型上界处饱和，饱和后变为空的元素被删除
+    fn shift_by_saturating(&mut self, delta: T::Type)
+    where
+        T::Type: Coordinate;
+
+    /// 在 `pivot` 处插入长度为 `delta` 的空白：起点不小于 `pivot` 的元素整体后移 `delta`，
+    /// 跨越 `pivot` 的元素被分割，右半部分后移
+    ///
+    /// `delta` 不大于 0 时不做任何事；坐标溢出时返回 `RangeError::Overflow` 且不修改集合。
+    fn shift_after(&mut self, pivot: T::Type, delta: T::Type) -> Result<(), RangeError<T>>
+    where
+        T::Type: Coordinate;
+
+    /// 删除 `range` 并将其后的坐标整体前移 `range` 的长度（对应文本删除），
+    /// 删除后首尾相接的同 kind 元素会被合并
+    ///
+    /// 与 `merge_remove` 一样不检查 `overwritable`；不会增加元素数量，因此不会失败。
+    fn collapse(&mut self, range: Range<T::Type>)
+    where
+        T::Type: Coordinate;
+
+    /// 添加 `start..T::Type::MAX`（“到无穷”），元数据取自 `template`，按 merge_add 规则合并
+    ///
+    /// `MAX` 本身不被覆盖，见 `contains_point`。
+    fn add_to_end(
+        &mut self,
+        start: T::Type,
+        template: &T,
+        temp: &mut [u8],
+    ) -> Result<(), RangeError<T>>
+    where
+        T::Type: Coordinate;
+
+    /// 创建从头开始的顺序扫描游标
+    fn cursor(&self) -> Cursor<'_, T>;
+
+    /// 查找覆盖 `range.start` 的元素，不存在时返回可在该空隙插入的 `Vacant`
+    ///
+    /// 只以 `range.start` 判断是否已占用；`range` 与后面覆盖部分重叠的情况在插入时报告为 `Conflict`。
+    fn entry(&mut self, range: Range<T::Type>) -> Entry<'_, Self, T>
+    where
+        Self: Sized;
+
+    /// 将 range 覆盖的区域改为 `template` 的 kind：先删除 range，再以 `template.clone_with_range(range)` 添加回去
+    ///
+    /// 新 kind 由 `template` 提供（`RangeInfo` 没有修改 kind 的接口）。与 merge_add 不同，
+    /// 不可覆盖的元素也会被改写。失败时恢复原状态。
+    fn reclassify(
+        &mut self,
+        range: Range<T::Type>,
+        template: &T,
+        temp: &mut [u8],
+    ) -> Result<(), RangeError<T>>;
+
+    /// 将 range 内已覆盖的部分标记为删除：改为 `sentinel` kind 而不是移除，空隙保持不变
+    ///
+    /// 与 `reclassify` 类似，但只改写已覆盖的部分，总覆盖范围不变。新元素为
+    /// `template.clone_with_range(..)`；`template.kind()` 与 `sentinel` 不一致时返回
+    /// `KindMismatch` 且不修改集合。失败时恢复原状态。
+    fn tombstone(
+        &mut self,
+        range: Range<T::Type>,
+        sentinel: T::Kind,
+        template: &T,
+        temp: &mut [u8],
+    ) -> Result<(), RangeError<T>>;
+
+    /// 并入 `other` 的所有元素；与不同 kind 元素重叠的每个子区间由 `resolve(已有, 新)` 决定保留哪个元素
+    ///
+    /// 传给 `resolve` 的元素已截断到重叠子区间，返回值也会被截断到该子区间。
+    /// 不检查 `overwritable`，其余部分按 merge_add 规则合并。失败时恢复原状态。
+    fn merge_with<F>(
+        &mut self,
+        other: &Self,
+        resolve: F,
+        temp: &mut [u8],
+    ) -> Result<(), RangeError<T>>
+    where
+        F: FnMut(&T, &T) -> T;
+
+    /// 添加一个区间；与不同 kind 元素重叠的部分由优先级较高的一方保留，优先级相同时新区间胜出
+    ///
+    /// 不检查 `overwritable`，同 kind 部分按 merge_add 规则合并。失败时恢复原状态。
+    fn merge_add_priority(&mut self, new_info: T, temp: &mut [u8]) -> Result<(), RangeError<T>>
+    where
+        T: PriorityRangeInfo;
+
+    /// 将 range 覆盖的元素的 `overwritable` 改为 `value`，用于“先预留、后锁定”
+    ///
+    /// 只有部分落在 range 内的元素会被拆开，range 内的部分使用新值，range 外的部分保留原值；
+    /// `overwritable` 不同的同 kind 元素相接时不会合并，修改后与相邻元素 kind 和 `overwritable`
+    /// 都相同时会合并。拆分所需的容量不足时返回 `Capacity` 且不修改集合。
+    fn set_overwritable(&mut self, range: Range<T::Type>, value: bool) -> Result<(), RangeError<T>>
+    where
+        T: LockableRangeInfo;
+
+    /// 元素（区间）的数量，与 `len()` 相同；覆盖的总长度请使用 `total_len()`
+    fn element_count(&self) -> usize;
+
+    /// 所有元素覆盖的总长度（覆盖点数），与元素数量 `element_count()` 不同
+    fn total_len(&self) -> T::Type
+    where
+        T::Type: Add<Output = T::Type> + Sub<Output = T::Type> + Default;
+
+    /// 把坐标视为字节偏移，返回覆盖的总字节数
+    ///
+    /// 与 `total_len()` 相同，但统一以 `u64` 返回，不受坐标类型宽度影响；超出时饱和到 `u64::MAX`。
+    fn covered_bytes(&self) -> u64
+    where
+        T::Type: Coordinate;
 }
 
 /// RangeSet 操作 trait（alloc 版本），为带分配器的容器提供区间集合功能
@@ -158,16 +757,493 @@ pub trait RangeVecAllocOps<T: RangeInfo> {
     /// 添加一个区间（会自动合并相邻区间）
     fn merge_add(&mut self, new_info: T) -> Result<(), RangeError<T>>;
 
+    /// 同 `merge_add`，成功时返回新区间最终所在元素（与邻居合并后）的区间
+    fn add_and_report(&mut self, new_info: T) -> Result<Range<T::Type>, RangeError<T>>;
+
     /// 删除一个区间
     fn merge_remove(&mut self, range: Range<T::Type>) -> Result<(), RangeError<T>>;
 
+    /// 同 `merge_remove`，并返回删除对元素数量的影响
+    fn remove_with_stats(&mut self, range: Range<T::Type>) -> Result<RemoveStats, RangeError<T>>;
+
+    /// 同 `merge_remove`，并按顺序返回被删除的片段；每个片段保留原元素的 kind 等信息，
+    /// 可直接重新 `merge_add` 用于撤销。内存分配失败时（启用 `fallible-alloc`）返回 `AllocFailed`
+    /// 且不修改集合。
+    fn remove_taking(&mut self, range: Range<T::Type>)
+    -> Result<alloc::vec::Vec<T>, RangeError<T>>;
+
+    /// 删除区间，但 `range` 覆盖到不可覆盖的元素时返回 `Conflict`，集合保持不变
+    ///
+    /// 不检查保护的删除请使用 `merge_remove`。
+    fn try_merge_remove(&mut self, range: Range<T::Type>) -> Result<(), RangeError<T>>;
+
+    /// 批量删除多个区间：排序后一次扫描集合，结果与逐个 `merge_remove` 相同
+    fn merge_remove_many<I>(&mut self, ranges: I) -> Result<(), RangeError<T>>
+    where
+        I: IntoIterator<Item = Range<T::Type>>;
+
+    /// 同 `merge_add`，过程中的合并、分割与冲突会通知 `observer`
+    fn merge_add_observed(
+        &mut self,
+        new_info: T,
+        observer: &mut impl MergeObserver<T>,
+    ) -> Result<(), RangeError<T>>;
+
+    /// 同 `merge_remove`，被切开的元素保留下来的片段会通知 `observer`
+    fn merge_remove_observed(
+        &mut self,
+        range: Range<T::Type>,
+        observer: &mut impl MergeObserver<T>,
+    ) -> Result<(), RangeError<T>>;
+
     /// 批量添加多个区间
     fn merge_extend<I>(&mut self, ranges: I) -> Result<(), RangeError<T>>
     where
         I: IntoIterator<Item = T>;
 
+    /// 批量添加多个区间：先按起点（稳定）排序，再逐个只改写受影响的元素窗口
+    ///
+    /// 每次添加不像 `merge_extend` 那样重建整个集合，但在集合中间插入仍需搬移其后的元素。
+    /// 覆盖结果（区间与 kind）以及错误与按原顺序 `merge_extend` 相同：输入之间存在不同 kind 的
+    /// 重叠、输入与不可覆盖的已有元素重叠或相接、不可覆盖的输入与已有元素重叠或相接，或添加
+    /// 出错时，退回按原顺序 `merge_extend`。同 kind 合并后元素的其余元数据可能来自
+    /// 不同的输入元素。heapless 容器可使用 `RangeSetBuilder` 达到相同效果。
+    fn merge_extend_sorted<I>(&mut self, ranges: I) -> Result<(), RangeError<T>>
+    where
+        I: IntoIterator<Item = T>;
+
+    /// 批量添加多个区间，成功时返回添加的数量；失败时返回失败前已添加的数量和错误
+    fn merge_extend_counted<I>(&mut self, ranges: I) -> Result<usize, (usize, RangeError<T>)>
+    where
+        I: IntoIterator<Item = T>;
+
+    /// 批量添加多个区间，任一区间失败时恢复到调用前的状态
+    fn merge_extend_atomic<I>(&mut self, ranges: I) -> Result<(), RangeError<T>>
+    where
+        I: IntoIterator<Item = T>;
+
     /// 查询某个值是否落在任意一个区间中
+    ///
+    /// 区间是半开的 `[start, end)`：以 `T::Type::MAX` 为终点表示“到无穷”时，`MAX` 本身永远不被包含。
     fn contains_point(&self, value: T::Type) -> bool;
+
+    /// 返回覆盖 `value` 的元素下标，可直接用于索引，避免再次查找
+    fn point_index(&self, value: T::Type) -> Option<usize>;
+
+    /// 查询 `range` 是否有任意部分被覆盖（部分重叠即可），`range` 为空时返回 false
+    ///
+    /// ```rust
+    /// use ranges_ext::{RangeVecAllocOps, UnitRange};
+    ///
+    /// let mut set = Vec::new();
+    /// set.merge_add(UnitRange::new(0..10u32)).unwrap();
+    ///
+    /// // 部分重叠即可
+    /// assert!(set.contains_any(5..15));
+    /// assert!(!set.contains_any(10..15));
+    /// ```
+    fn contains_any(&self, range: Range<T::Type>) -> bool;
+
+    /// 查询 `range` 是否被完全覆盖（可以跨越多个首尾相接的元素），`range` 为空时返回 true
+    ///
+    /// ```rust
+    /// use ranges_ext::{RangeVecAllocOps, UnitRange};
+    ///
+    /// let mut set = Vec::new();
+    /// set.merge_add(UnitRange::new(0..10u32)).unwrap();
+    ///
+    /// // 部分覆盖的区间不算被包含
+    /// assert!(set.contains_all(2..8));
+    /// assert!(!set.contains_all(5..15));
+    /// ```
+    fn contains_all(&self, range: Range<T::Type>) -> bool;
+
+    /// 检查集合内部不变量：有序、不重叠、非空，且相邻同 kind、同 `overwritable` 的区间已合并
+    fn validate(&self) -> Result<(), InvariantError>;
+
+    /// 删除所有 kind 等于给定值的区间，保留其余区间（不会重新合并）
+    fn remove_by_kind(&mut self, kind: &T::Kind);
+
+    /// 删除所有满足谓词的区间，保留其余区间（不会重新合并）
+    fn remove_if<F>(&mut self, f: F)
+    where
+        F: FnMut(&T) -> bool;
+
+    /// 删除并返回坐标最低的元素；需要移动其余元素，O(n)
+    fn pop_first(&mut self) -> Option<T>;
+
+    /// 删除并返回坐标最高的元素，O(1)
+    fn pop_last(&mut self) -> Option<T>;
+
+    /// 删除长度小于 `min_len` 的元素，留下空隙（不会重新合并）
+    fn drop_smaller_than(&mut self, min_len: T::Type)
+    where
+        T::Type: Coordinate;
+
+    /// 依次返回每个元素的 (区间, kind)
+    ///
+    /// `RangeInfo::kind` 按值返回，这里的 kind 同样是克隆出的值。
+    fn ranges_with_kind(&self) -> impl Iterator<Item = (Range<T::Type>, T::Kind)>;
+
+    /// 按连续相同的 kind 分组统计，返回每组的 (kind, 元素数量, 覆盖长度)
+    fn count_by_kind(&self) -> impl Iterator<Item = (T::Kind, usize, T::Type)>
+    where
+        T::Type: Sub<Output = T::Type> + Add<Output = T::Type>;
+
+    /// 将所有元素克隆到 `out` 中（会先清空 `out`），容量不足时返回 `Capacity` 且不修改 `out`
+    fn collect_into<const M: usize>(
+        &self,
+        out: &mut heapless::Vec<T, M>,
+    ) -> Result<(), RangeError<T>>;
+
+    /// 只收集每个元素的 `[start, end)` 区间，不克隆元素，容量不足时返回 `Capacity`
+    fn copied_ranges<const M: usize>(
+        &self,
+    ) -> Result<heapless::Vec<Range<T::Type>, M>, RangeError<T>>;
+
+    /// 以切片形式借用内部元素，不发生拷贝；任何修改操作都会结束该借用
+    fn as_element_slice(&self) -> &[T];
+
+    /// 以切片形式借用与 bounds 重叠的所有元素（元素有序，重叠的元素总是连续的），
+    /// 首尾元素可能超出 bounds；bounds 为空或没有重叠时返回空切片
+    fn elements_slice(&self, bounds: Range<T::Type>) -> &[T];
+
+    /// 只收集覆盖范围：首尾相接或重叠的元素不论 kind 都合并为一个区间
+    ///
+    /// 元素本身请直接使用 `as_slice()`，它借用内部存储，不发生拷贝。
+    fn merged_ranges(&self) -> alloc::vec::Vec<Range<T::Type>>;
+
+    /// 快速追加已排序且互不重叠的区间，每个区间只与当前末尾元素合并
+    ///
+    /// 调用方需保证每个区间的起点不小于前一个区间（以及集合末尾元素）的终点，
+    /// debug 模式下会断言该前提。出错时已追加的区间会保留。
+    fn extend_sorted_disjoint<I>(&mut self, ranges: I) -> Result<(), RangeError<T>>
+    where
+        I: IntoIterator<Item = T>;
+
+    /// 用已构建好的元素整体替换集合内容，不逐个 `merge_add`
+    ///
+    /// 调用方需保证 `elements` 满足集合不变量（已排序、互不重叠、相邻同 kind 已合并），
+    /// debug 模式下会断言该前提，release 模式只做 O(1) 的交换，不会失败。
+    fn replace_all(&mut self, elements: alloc::vec::Vec<T>);
+
+    /// 查询区间内的每个点是否都被 kind 等于给定值的元素覆盖
+    fn covered_by_kind(&self, range: Range<T::Type>, kind: &T::Kind) -> bool;
+
+    /// 返回 bounds 内未被任何元素覆盖的空隙（按顺序）
+    ///
+    /// 空隙已截断到 bounds 内，即查询区间中尚未覆盖的部分，例如缓存中需要补取的范围。
+    fn gaps_within(&self, bounds: Range<T::Type>) -> impl Iterator<Item = Range<T::Type>>;
+
+    /// 返回 query 中未被任何元素覆盖的部分（按顺序），与 `gaps_within(query)` 相同
+    fn uncovered_within(&self, query: Range<T::Type>) -> impl Iterator<Item = Range<T::Type>>;
+
+    /// 返回 range 内第一个未被覆盖的坐标，完全覆盖（或 range 为空）时返回 `None`
+    fn first_uncovered(&self, range: Range<T::Type>) -> Option<T::Type>;
+
+    /// 首次适配：返回 bounds 内第一个能容纳 size 的空隙中、从其起点开始长度为 size 的区间
+    fn first_fit(&self, size: T::Type, bounds: Range<T::Type>) -> Option<Range<T::Type>>
+    where
+        T::Type: Add<Output = T::Type> + Sub<Output = T::Type>;
+
+    /// 最佳适配：返回 bounds 内能容纳 size 的最小空隙中、从其起点开始长度为 size 的区间
+    fn best_fit(&self, size: T::Type, bounds: Range<T::Type>) -> Option<Range<T::Type>>
+    where
+        T::Type: Add<Output = T::Type> + Sub<Output = T::Type>;
+
+    /// 在 bounds 内按首次适配分配长度为 size 的区间，用 `make` 构造元素并插入集合
+    ///
+    /// 没有合适空隙时返回 `RangeError::NoFit`。
+    fn allocate<F>(
+        &mut self,
+        size: T::Type,
+        bounds: Range<T::Type>,
+        make: F,
+    ) -> Result<Range<T::Type>, RangeError<T>>
+    where
+        T::Type: Add<Output = T::Type> + Sub<Output = T::Type>,
+        F: FnOnce(Range<T::Type>) -> T;
+
+    /// 严格删除：range 中任何部分未被覆盖时返回 `RangeError::NotAllocated` 且不修改集合
+    fn deallocate(&mut self, range: Range<T::Type>) -> Result<(), RangeError<T>>;
+
+    /// 调整与 `old` 完全相同的元素的终点：增长时按 merge_add 规则合并或报告冲突，缩小时截断尾部
+    ///
+    /// 找不到与 `old` 完全相同的元素时返回 `RangeError::NotAllocated`；`new_end` 不大于 `old.start`
+    /// （缩小为空区间）时返回 `RangeError::EmptyRange`，删除整个元素请使用 `merge_remove`。
+    fn resize_element(
+        &mut self,
+        old: Range<T::Type>,
+        new_end: T::Type,
+    ) -> Result<(), RangeError<T>>;
+
+    /// 将 `from` 覆盖的内容（保留 kind）移动到以 `to_start` 为起点的等长区间
+    ///
+    /// `from` 未被完全覆盖时返回 `RangeError::NotAllocated`；目标位置冲突时返回错误并恢复原状态。
+    fn relocate(&mut self, from: Range<T::Type>, to_start: T::Type) -> Result<(), RangeError<T>>
+    where
+        T::Type: Add<Output = T::Type> + Sub<Output = T::Type>;
+
+    /// 返回添加 `info` 时会产生冲突的所有已有元素，不修改集合
+    fn conflicts_for(&self, info: &T) -> alloc::vec::Vec<&T>;
+
+    /// 预览 merge_add 的结果：克隆 `self` 并在副本上执行添加，返回副本，`self` 保持不变
+    ///
+    /// 每次调用都会克隆整个集合。
+    fn preview_add(&self, info: T) -> Result<Self, RangeError<T>>
+    where
+        Self: Sized;
+
+    /// 按顺序遍历 bounds 内的已覆盖元素和空隙，包括首尾相对 bounds 的空隙
+    fn segments<'a>(&'a self, bounds: Range<T::Type>) -> impl Iterator<Item = Segment<'a, T>>
+    where
+        T: 'a;
+
+    /// 按顺序比较两个集合的覆盖情况：把至少一侧覆盖的部分切成片段，
+    /// 每个片段标明只被左侧、只被右侧还是被两侧覆盖，以及对应的 kind
+    ///
+    /// 任一侧的元素边界都会切开片段；两侧都未覆盖的空隙不产生片段。
+    fn relate<'a>(&'a self, other: &'a Self) -> impl Iterator<Item = Relation<T>>
+    where
+        T: 'a;
+
+    /// 判断两个集合是否没有公共覆盖点，只比较区间几何，不考虑 kind
+    fn is_disjoint_from(&self, other: &Self) -> bool;
+
+    /// 判断本集合的每个覆盖点是否都被 `other` 覆盖，只比较区间几何，不考虑 kind
+    fn is_subset_of(&self, other: &Self) -> bool;
+
+    /// 判断本集合的每个覆盖点是否都被 `other` 中 kind 相同的元素覆盖
+    fn is_subset_of_with_kind(&self, other: &Self) -> bool;
+
+    /// 添加一个区间，被分割的已有元素保留下来的每个片段都会先传给 `on_split` 调整元数据
+    ///
+    /// `merge_add` 保持片段原有的元数据不变。
+    fn merge_add_with_split_hook<F>(
+        &mut self,
+        new_info: T,
+        on_split: F,
+    ) -> Result<(), RangeError<T>>
+    where
+        F: FnMut(&mut T);
+
+    /// 批量添加共享同一元数据的多个区间，元数据取自 `template`
+    ///
+    /// 输入会先排序并合并，再一次扫描并入集合；出错时集合保持调用前的状态。
+    fn add_many_same_kind<I>(&mut self, ranges: I, template: &T) -> Result<(), RangeError<T>>
+    where
+        I: IntoIterator<Item = Range<T::Type>>;
+
+    /// 释放多余的容量，返回释放的字节数（尽力而为），不改变集合内容
+    fn compact(&mut self) -> usize;
+
+    /// 预留至少还能容纳 `additional` 个元素的空间，分配失败时返回错误而不是中止
+    ///
+    /// 转发到 `Vec::try_reserve`；直接在 `Vec` 上以方法语法调用时解析为同名的固有方法，行为相同。
+    fn try_reserve(&mut self, additional: usize)
+    -> Result<(), alloc::collections::TryReserveError>;
+
+    /// 将所有元素复制到 `dst` 中并覆盖其原有内容，尽量复用 `dst` 已有的分配
+    ///
+    /// 命名避开了 `ToOwned::clone_into`，以免调用时产生歧义。
+    fn snapshot_into(&self, dst: &mut Self);
+
+    /// 保存当前状态，之后可通过 `restore` 撤销期间的所有修改
+    fn snapshot(&self) -> Snapshot<Self>
+    where
+        Self: Sized;
+
+    /// 将集合恢复为 `snapshot` 保存时的状态
+    fn restore(&mut self, snapshot: Snapshot<Self>)
+    where
+        Self: Sized;
+
+    /// 删除与 bounds 没有交集的元素，与 bounds 部分重叠的元素原样保留（不截断）
+    fn retain_within(&mut self, bounds: Range<T::Type>);
+
+    /// 批量添加裸区间，每个区间通过 `make` 构造成元素后按 merge_add 规则插入
+    fn add_ranges<F>(&mut self, ranges: &[Range<T::Type>], make: F) -> Result<(), RangeError<T>>
+    where
+        F: Fn(Range<T::Type>) -> T;
+
+    /// 查询给定区间中是否有任意一个与已有覆盖重叠，命中后立即返回
+    fn any_overlap(&self, ranges: &[Range<T::Type>]) -> bool;
+
+    /// 添加一个区间；`strict` 为 true 时空区间返回 `RangeError::EmptyRange`，否则与 merge_add 一样忽略
+    fn merge_add_checked(&mut self, new_info: T, strict: bool) -> Result<(), RangeError<T>>;
+
+    /// 严格添加一个区间：空区间返回 `RangeError::EmptyRange` 而不是被忽略
+    fn merge_add_strict(&mut self, new_info: T) -> Result<(), RangeError<T>>;
+
+    /// 添加一个区间；新区间不可覆盖时只能落在空隙中，与任意已有元素（无论 kind 与是否可覆盖）
+    /// 重叠都返回 `Conflict`。可覆盖的新区间与 merge_add 相同。
+    fn merge_add_exclusive(&mut self, new_info: T) -> Result<(), RangeError<T>>;
+
+    /// 同 `merge_add`，但区间部分或全部落在 `universe` 之外时返回 `OutOfBounds` 且不修改集合
+    ///
+    /// 空区间与 merge_add 一样被忽略，不做检查。
+    fn merge_add_within(
+        &mut self,
+        new_info: T,
+        universe: Range<T::Type>,
+    ) -> Result<(), RangeError<T>>;
+
+    /// 同 `merge_add`，并报告新区间是单独插入、与同 kind 元素合并，还是覆盖了其他 kind 的元素
+    fn merge_add_notifying(&mut self, new_info: T) -> Result<AddOutcome<T>, RangeError<T>>;
+
+    /// 计算 bounds 内被覆盖部分所占的比例（0.0 ~ 1.0），bounds 为空时返回 0.0
+    fn coverage_ratio(&self, bounds: Range<T::Type>) -> f64
+    where
+        T::Type: Coordinate;
+
+    /// 合并首尾相接的同 kind 相邻元素，恢复 `chunk_elements` 等操作之前的合并状态
+    fn coalesce(&mut self);
+
+    /// 合并首尾相接且 kind 满足 `eq` 的相邻元素，合并结果沿用左侧元素的 kind
+    fn coalesce_by<F>(&mut self, eq: F)
+    where
+        F: FnMut(&T::Kind, &T::Kind) -> bool;
+
+    /// 将长度超过 `stride` 的元素切分为若干长度不超过 `stride` 的连续片段，覆盖范围和 kind 不变
+    ///
+    /// 切分后相邻的同 kind 元素首尾相接，不再满足合并不变量（`validate` 报告 `Unmerged`），
+    /// 之后的添加与删除照常可用；可用 `coalesce` 恢复。
+    /// 内存分配失败时（启用 `fallible-alloc`）返回 `AllocFailed` 且不修改集合。
+    fn chunk_elements(&mut self, stride: T::Type) -> Result<(), RangeError<T>>
+    where
+        T::Type: Add<Output = T::Type> + Sub<Output = T::Type>;
+
+    /// 按顺序返回每一对相邻元素
+    fn pairs<'a>(&'a self) -> impl Iterator<Item = (&'a T, &'a T)>
+    where
+        T: 'a;
+
+    /// 返回相邻元素 kind 发生变化的位置，即后一个元素的起点
+    ///
+    /// 无论两个元素首尾相接还是中间隔着空隙都算作一次变化；kind 相同的元素之间即使有空隙也不算。
+    fn kind_boundaries(&self) -> impl Iterator<Item = T::Type>;
+
+    /// 按顺序返回每个元素（合并后）的区间，与元素是否保留原始子区间无关
+    fn merged_iter(&self) -> impl Iterator<Item = Range<T::Type>>;
+
+    /// 按元素顺序展开每个元素保留的原始子区间
+    fn originals_iter(&self) -> impl Iterator<Item = Range<T::Type>>
+    where
+        T: ProvenanceRangeInfo;
+
+    /// 将所有元素向后平移 `delta`；任一坐标溢出时返回 `RangeError::Overflow` 且不修改集合
+    fn shift_by(&mut self, delta: T::Type) -> Result<(), RangeError<T>>
+    where
+        T::Type: Coordinate;
+
+    /// 将所有元素向后平移 `delta`，坐标在类型上界处饱和，饱和后变为空的元素被删除
+    fn shift_by_saturating(&mut self, delta: T::Type)
+    where
+        T::Type: Coordinate;
+
+    /// 在 `pivot` 处插入长度为 `delta` 的空白：起点不小于 `pivot` 的元素整体后移 `delta`，
+    /// 跨越 `pivot` 的元素被分割，右半部分后移
+    ///
+    /// `delta` 不大于 0 时不做任何事；坐标溢出时返回 `RangeError::Overflow` 且不修改集合。
+    fn shift_after(&mut self, pivot: T::Type, delta: T::Type) -> Result<(), RangeError<T>>
+    where
+        T::Type: Coordinate;
+
+    /// 删除 `range` 并将其后的坐标整体前移 `range` 的长度（对应文本删除），
+    /// 删除后首尾相接的同 kind 元素会被合并
+    ///
+    /// 与 `merge_remove` 一样不检查 `overwritable`；不会增加元素数量，因此不会失败。
+    fn collapse(&mut self, range: Range<T::Type>)
+    where
+        T::Type: Coordinate;
+
+    /// 添加 `start..T::Type::MAX`（“到无穷”），元数据取自 `template`，按 merge_add 规则合并
+    ///
+    /// `MAX` 本身不被覆盖，见 `contains_point`。
+    fn add_to_end(&mut self, start: T::Type, template: &T) -> Result<(), RangeError<T>>
+    where
+        T::Type: Coordinate;
+
+    /// 从起点到终点的 `BTreeMap` 构造集合，每个区间通过 `make` 构造成元素并按 merge_add 规则合并
+    ///
+    /// map 形式不携带 kind 等元数据，需要由 `make` 提供。
+    fn from_btreemap<F>(
+        map: alloc::collections::BTreeMap<T::Type, T::Type>,
+        make: F,
+    ) -> Result<Self, RangeError<T>>
+    where
+        Self: Sized,
+        F: Fn(Range<T::Type>) -> T;
+
+    /// 导出为起点到终点的 `BTreeMap`，kind 等元数据会丢失
+    fn to_btreemap(&self) -> alloc::collections::BTreeMap<T::Type, T::Type>;
+
+    /// 创建从头开始的顺序扫描游标
+    fn cursor(&self) -> Cursor<'_, T>;
+
+    /// 查找覆盖 `range.start` 的元素，不存在时返回可在该空隙插入的 `Vacant`
+    ///
+    /// 只以 `range.start` 判断是否已占用；`range` 与后面覆盖部分重叠的情况在插入时报告为 `Conflict`。
+    fn entry(&mut self, range: Range<T::Type>) -> Entry<'_, Self, T>
+    where
+        Self: Sized;
+
+    /// 将 range 覆盖的区域改为 `template` 的 kind：先删除 range，再以 `template.clone_with_range(range)` 添加回去
+    ///
+    /// 新 kind 由 `template` 提供（`RangeInfo` 没有修改 kind 的接口）。与 merge_add 不同，
+    /// 不可覆盖的元素也会被改写。失败时恢复原状态。
+    fn reclassify(&mut self, range: Range<T::Type>, template: &T) -> Result<(), RangeError<T>>;
+
+    /// 将 range 内已覆盖的部分标记为删除：改为 `sentinel` kind 而不是移除，空隙保持不变
+    ///
+    /// 与 `reclassify` 类似，但只改写已覆盖的部分，总覆盖范围不变。新元素为
+    /// `template.clone_with_range(..)`；`template.kind()` 与 `sentinel` 不一致时返回
+    /// `KindMismatch` 且不修改集合。失败时恢复原状态。
+    fn tombstone(
+        &mut self,
+        range: Range<T::Type>,
+        sentinel: T::Kind,
+        template: &T,
+    ) -> Result<(), RangeError<T>>;
+
+    /// 并入 `other` 的所有元素；与不同 kind 元素重叠的每个子区间由 `resolve(已有, 新)` 决定保留哪个元素
+    ///
+    /// 传给 `resolve` 的元素已截断到重叠子区间，返回值也会被截断到该子区间。
+    /// 不检查 `overwritable`，其余部分按 merge_add 规则合并。失败时恢复原状态。
+    fn merge_with<F>(&mut self, other: &Self, resolve: F) -> Result<(), RangeError<T>>
+    where
+        F: FnMut(&T, &T) -> T;
+
+    /// 添加一个区间；与不同 kind 元素重叠的部分由优先级较高的一方保留，优先级相同时新区间胜出
+    ///
+    /// 不检查 `overwritable`，同 kind 部分按 merge_add 规则合并。失败时恢复原状态。
+    fn merge_add_priority(&mut self, new_info: T) -> Result<(), RangeError<T>>
+    where
+        T: PriorityRangeInfo;
+
+    /// 将 range 覆盖的元素的 `overwritable` 改为 `value`，用于“先预留、后锁定”
+    ///
+    /// 只有部分落在 range 内的元素会被拆开，range 内的部分使用新值，range 外的部分保留原值；
+    /// `overwritable` 不同的同 kind 元素相接时不会合并，修改后与相邻元素 kind 和 `overwritable`
+    /// 都相同时会合并。
+    fn set_overwritable(&mut self, range: Range<T::Type>, value: bool) -> Result<(), RangeError<T>>
+    where
+        T: LockableRangeInfo;
+
+    /// 元素（区间）的数量，与 `len()` 相同；覆盖的总长度请使用 `total_len()`
+    fn element_count(&self) -> usize;
+
+    /// 所有元素覆盖的总长度（覆盖点数），与元素数量 `element_count()` 不同
+    fn total_len(&self) -> T::Type
+    where
+        T::Type: Add<Output = T::Type> + Sub<Output = T::Type> + Default;
+
+    /// 把坐标视为字节偏移，返回覆盖的总字节数
+    ///
+    /// 与 `total_len()` 相同，但统一以 `u64` 返回，不受坐标类型宽度影响；超出时饱和到 `u64::MAX`。
+    fn covered_bytes(&self) -> u64
+    where
+        T::Type: Coordinate;
 }
 
 /// RangeSet 错误类型
@@ -187,13 +1263,259 @@ where
         /// 已存在的冲突区间
         existing: T,
     },
+    /// 没有足够大的空隙可供分配
+    #[error("No gap large enough for the requested allocation")]
+    NoFit,
+    /// 要释放的区间中存在未被覆盖的部分
+    #[error("Range was not fully allocated")]
+    NotAllocated,
+    /// 严格模式下添加了空区间（start >= end）
+    #[error("Empty range rejected in strict mode")]
+    EmptyRange,
+    /// 坐标运算溢出
+    #[error("Coordinate arithmetic overflowed")]
+    Overflow,
+    /// 内存分配失败（启用 `fallible-alloc` 时由 alloc 后端返回）
+    #[error("Memory allocation failed")]
+    AllocFailed,
+    /// 区间部分或全部落在允许的坐标范围之外
+    #[error("Range {range:?} is outside the universe {universe:?}")]
+    OutOfBounds {
+        /// 被拒绝的区间
+        range: Range<T::Type>,
+        /// 允许的坐标范围
+        universe: Range<T::Type>,
+    },
+    /// 模板元素的 kind 与要求的 kind 不一致
+    #[error("Template kind {found:?} does not match the expected kind {expected:?}")]
+    KindMismatch {
+        /// 要求的 kind
+        expected: T::Kind,
+        /// 模板元素的 kind
+        found: T::Kind,
+    },
+}
+
+/// `remove_with_stats` 对元素数量的影响
+///
+/// 只被截掉一端的元素既不计入 `removed` 也不计入 `split`。
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RemoveStats {
+    /// 被整体删除的元素数量
+    pub removed: usize,
+    /// 从中间被分割成两段的元素数量
+    pub split: usize,
+    /// 删除后元素数量的变化
+    pub remaining_delta: isize,
+}
+
+/// `merge_add_notifying` 的结果
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AddOutcome<T> {
+    /// 新区间没有与任何元素重叠或相接，作为新元素插入（空区间同样返回此值）
+    Inserted,
+    /// 新区间与同 kind 元素重叠或相接并合并，没有覆盖其他 kind
+    MergedSameKind,
+    /// 新区间覆盖了不同 kind 的元素，按顺序列出被覆盖的部分（已截断到新区间内）
+    Overrode(alloc::vec::Vec<T>),
+}
+
+/// 按顺序遍历集合时产生的片段
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Segment<'a, T: RangeInfo> {
+    /// 已覆盖的元素（不截断到遍历范围）
+    Covered(&'a T),
+    /// 未被覆盖的空隙
+    Gap(Range<T::Type>),
+}
+
+/// `relate` 产生的片段：一段区间，以及覆盖它的一侧或两侧元素的 kind
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Relation<T: RangeInfo> {
+    /// 只被左侧（`self`）覆盖
+    OnlyLeft {
+        range: Range<T::Type>,
+        kind: T::Kind,
+    },
+    /// 只被右侧（`other`）覆盖
+    OnlyRight {
+        range: Range<T::Type>,
+        kind: T::Kind,
+    },
+    /// 两侧都覆盖
+    Both {
+        range: Range<T::Type>,
+        left_kind: T::Kind,
+        right_kind: T::Kind,
+    },
+}
+
+impl<T: RangeInfo> Relation<T> {
+    /// 片段对应的区间
+    pub fn range(&self) -> &Range<T::Type> {
+        match self {
+            Relation::OnlyLeft { range, .. }
+            | Relation::OnlyRight { range, .. }
+            | Relation::Both { range, .. } => range,
+        }
+    }
+}
+
+/// 集合不变量被破坏时的错误类型
+#[derive(Clone, Copy, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum InvariantError {
+    /// 区间为空（start >= end）
+    #[error("element {index} is an empty range")]
+    EmptyRange {
+        /// 空区间的下标
+        index: usize,
+    },
+    /// 区间未按起点排序
+    #[error("elements {left} and {right} are not sorted by start")]
+    Unsorted {
+        /// 左侧区间的下标
+        left: usize,
+        /// 右侧区间的下标
+        right: usize,
+    },
+    /// 相邻区间重叠
+    #[error("elements {left} and {right} overlap")]
+    Overlap {
+        /// 左侧区间的下标
+        left: usize,
+        /// 右侧区间的下标
+        right: usize,
+    },
+    /// 相邻同 kind、同 `overwritable` 区间首尾相接但未合并
+    #[error(
+        "elements {left} and {right} have the same kind and overwritable flag and touch but were not merged"
+    )]
+    Unmerged {
+        /// 左侧区间的下标
+        left: usize,
+        /// 右侧区间的下标
+        right: usize,
+    },
+}
+
+/// 区间坐标的算术，需要计算坐标的方法统一以 `T::Type: Coordinate` 为约束
+///
+/// 已为所有整数类型实现。`char` 等非整数坐标没有实现，对应的方法（如 `shift_by`、
+/// `total_len`）不可用，但合并、删除、查询等集合操作只要求 `Ord + Copy`，照常可用。
+pub trait Coordinate: Copy {
+    /// 零
+    const ZERO: Self;
+    /// 类型上界
+    const MAX: Self;
+
+    fn saturating_add(self, rhs: Self) -> Self;
+    fn saturating_sub(self, rhs: Self) -> Self;
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+    /// 无损转换为 `i128`，超出范围（仅 `u128`）时返回 `None`
+    fn to_i128(self) -> Option<i128>;
+    /// 无损转换为 `u128`，负数返回 `None`
+    fn to_u128(self) -> Option<u128>;
 }
 
+macro_rules! impl_coordinate {
+    ($($t:ty),*) => {
+        $(
+            impl Coordinate for $t {
+                const ZERO: Self = 0;
+                const MAX: Self = <$t>::MAX;
+
+                #[inline]
+                fn saturating_add(self, rhs: Self) -> Self {
+                    <$t>::saturating_add(self, rhs)
+                }
+
+                #[inline]
+                fn saturating_sub(self, rhs: Self) -> Self {
+                    <$t>::saturating_sub(self, rhs)
+                }
+
+                #[inline]
+                fn checked_add(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_add(self, rhs)
+                }
+
+                #[inline]
+                fn checked_sub(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_sub(self, rhs)
+                }
+
+                #[inline]
+                fn to_i128(self) -> Option<i128> {
+                    i128::try_from(self).ok()
+                }
+
+                #[inline]
+                fn to_u128(self) -> Option<u128> {
+                    u128::try_from(self).ok()
+                }
+            }
+        )*
+    };
+}
+
+impl_coordinate!(
+    u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize
+);
+
+/// 区间元素 trait，集合中的每个元素都需要实现
 pub trait RangeInfo: Debug + Clone + Sized + Default {
+    /// 区间的分类，只有 kind 相同的相邻区间才会合并
     type Kind: Debug + Eq + Clone;
+    /// 区间坐标类型，例如整数或 `char`；需要坐标运算的方法另需 [`Coordinate`]
     type Type: Ord + Copy;
+    /// 返回区间 `[start, end)`
+    ///
+    /// 按值返回：坐标类型是 `Copy`，构造 `Range` 的开销与返回引用相当，
+    /// 且实现方不必在元素中存储 `Range`（例如分开存储起点和长度）。
     fn range(&self) -> Range<Self::Type>;
+    /// 返回区间的 kind
     fn kind(&self) -> Self::Kind;
+    /// 是否允许被不同 kind 的新区间覆盖
+    ///
+    /// 同 kind 的新区间与不可覆盖的元素重叠时，合并结果仍不可覆盖；首尾相接而 `overwritable`
+    /// 不同的同 kind 元素不会合并。
     fn overwritable(&self) -> bool;
+    /// 复制元数据并替换区间，用于分割与合并
     fn clone_with_range(&self, range: Range<Self::Type>) -> Self;
+    /// 按 `at` 切开元素，返回 `[左侧剩余, 右侧剩余]`，删除和覆盖时用来生成保留下来的片段
+    ///
+    /// 默认使用 [`split_range`]（即 `clone_with_range`）。需要按比例拆分内部数据等情况可以重写，
+    /// 但返回片段的区间必须与默认实现一致，只能调整其他元数据。
+    fn split(&self, at: &Range<Self::Type>) -> [Option<Self>; 2] {
+        helpers::split_range(self, at)
+    }
+    /// 同 kind 元素合并时调用：`self` 为合并后保留的元素（已设置合并后的区间），`other` 为被吸收的元素
+    ///
+    /// 默认不做任何事。可用于累加计数等元数据；添加与已有元素完全相同的区间也属于合并。
+    fn merge_meta(&mut self, _other: &Self) {}
+}
+
+/// 带优先级的区间元素，用于 `merge_add_priority`
+pub trait PriorityRangeInfo: RangeInfo {
+    /// 优先级类型，越大越优先
+    type Priority: Ord;
+    /// 返回元素的优先级
+    fn priority(&self) -> Self::Priority;
+}
+
+/// 可以修改 `overwritable` 的区间元素，用于 `set_overwritable`
+pub trait LockableRangeInfo: RangeInfo {
+    /// 设置是否允许被不同 kind 的新区间覆盖
+    fn set_overwritable(&mut self, overwritable: bool);
+}
+
+/// 保留合并前原始子区间的区间元素，用于 `originals_iter`
+///
+/// 原始子区间由实现自行维护：通常在 `merge_meta` 中并入另一元素的子区间，
+/// 在 `clone_with_range` 中裁剪到新区间内。
+pub trait ProvenanceRangeInfo: RangeInfo {
+    /// 按起点顺序返回并入本元素的原始子区间
+    fn originals(&self) -> impl Iterator<Item = Range<Self::Type>>;
 }
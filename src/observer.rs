@@ -0,0 +1,30 @@
+use crate::RangeInfo;
+
+/// 观察 merge_add / merge_remove 过程中的合并、分割与冲突，便于在无法调试的环境中记录日志
+///
+/// 所有回调默认什么都不做；使用 [`NoopObserver`] 时调用会被完全内联消除。
+pub trait MergeObserver<T: RangeInfo> {
+    /// 新区间与已有的同 kind 元素合并后调用，`merged` 为合并结果
+    fn on_merge(&mut self, _merged: &T) {}
+
+    /// 已有元素被切开后，对每个保留下来的片段调用（写入集合之前，可以修改片段）
+    fn on_split(&mut self, _part: &mut T) {}
+
+    /// 新区间与不可覆盖的元素冲突时调用，随后操作返回 `Conflict`
+    fn on_conflict(&mut self, _new: &T, _existing: &T) {}
+}
+
+/// 不做任何事的观察者
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct NoopObserver;
+
+impl<T: RangeInfo> MergeObserver<T> for NoopObserver {}
+
+/// 将 `merge_add_with_temp_and_split_hook` 的闭包适配为观察者
+pub(crate) struct SplitHook<F>(pub(crate) F);
+
+impl<T: RangeInfo, F: FnMut(&mut T)> MergeObserver<T> for SplitHook<F> {
+    fn on_split(&mut self, part: &mut T) {
+        (self.0)(part);
+    }
+}
@@ -0,0 +1,56 @@
+use core::cmp::Ordering;
+
+macro_rules! ordered_float {
+    ($(#[$meta:meta])* $name:ident, $float:ty) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, Debug, Default)]
+        pub struct $name(pub $float);
+
+        impl $name {
+            /// 包装一个浮点数，NaN 返回 `None`
+            pub fn new(value: $float) -> Option<Self> {
+                (!value.is_nan()).then_some(Self(value))
+            }
+
+            /// 取出内部的浮点数
+            pub const fn get(self) -> $float {
+                self.0
+            }
+        }
+
+        impl PartialEq for $name {
+            fn eq(&self, other: &Self) -> bool {
+                self.cmp(other) == Ordering::Equal
+            }
+        }
+
+        impl Eq for $name {}
+
+        impl PartialOrd for $name {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl Ord for $name {
+            fn cmp(&self, other: &Self) -> Ordering {
+                self.0.total_cmp(&other.0)
+            }
+        }
+    };
+}
+
+ordered_float!(
+    /// 按 `f64::total_cmp` 全序比较的 `f64`，可用作 `RangeInfo::Type`
+    ///
+    /// 全序下 `-0.0 < +0.0`，正 NaN 大于 `+∞`、负 NaN 小于 `-∞`。区间端点是 NaN 时
+    /// 不会破坏集合的排序，但几何上没有意义，构造时请用 `new` 排除 NaN。
+    OrdF64,
+    f64
+);
+
+ordered_float!(
+    /// 按 `f32::total_cmp` 全序比较的 `f32`，NaN 的处理与 [`OrdF64`] 相同
+    OrdF32,
+    f32
+);
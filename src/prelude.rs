@@ -1 +1,8 @@
-pub use crate::{RangeError, RangeInfo, RangeVecAllocOps, RangeVecOps};
+pub use crate::{
+    Coordinate, Cursor, Entry, InvariantError, LayeredRangeSet, LockableRangeInfo, MergeObserver,
+    NoopObserver, PriorityRangeInfo, ProvenanceRangeInfo, RangeError, RangeInfo, RangeSet,
+    RangeSetBuilder, RangeVecOps, Relation, RemoveStats, Segment, Snapshot, UnitRange,
+};
+
+#[cfg(feature = "alloc")]
+pub use crate::{AddOutcome, RangeVecAllocOps};
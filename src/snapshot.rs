@@ -0,0 +1,17 @@
+/// `snapshot` 保存的集合状态，只能通过 `restore` 恢复
+///
+/// 目前是对整个容器的一份拷贝。
+#[derive(Clone, Debug)]
+pub struct Snapshot<S> {
+    elements: S,
+}
+
+impl<S> Snapshot<S> {
+    pub(crate) fn new(elements: S) -> Self {
+        Self { elements }
+    }
+
+    pub(crate) fn into_inner(self) -> S {
+        self.elements
+    }
+}
@@ -0,0 +1,105 @@
+use core::{fmt::Debug, ops::Range};
+
+use crate::{LockableRangeInfo, RangeInfo};
+
+/// 不带 kind（`Kind = ()`）的内置区间元素，所有元素都可以互相合并
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct UnitRange<T> {
+    /// 区间 `[start, end)`
+    pub range: Range<T>,
+    /// 是否允许被覆盖
+    pub overwritable: bool,
+}
+
+impl<T> UnitRange<T> {
+    /// 可被覆盖的区间
+    pub const fn new(range: Range<T>) -> Self {
+        Self {
+            range,
+            overwritable: true,
+        }
+    }
+
+    /// 不可被覆盖的区间
+    pub const fn protected(range: Range<T>) -> Self {
+        Self {
+            range,
+            overwritable: false,
+        }
+    }
+}
+
+/// 与 `new` 一致，默认可被覆盖
+impl<T: Default> Default for UnitRange<T> {
+    fn default() -> Self {
+        Self::new(T::default()..T::default())
+    }
+}
+
+impl<T: Copy> UnitRange<T> {
+    /// 区间起点；遍历元素的热循环中优先使用，不需要构造 `Range`
+    #[inline]
+    pub const fn start(&self) -> T {
+        self.range.start
+    }
+
+    /// 区间终点（不包含）；遍历元素的热循环中优先使用，不需要构造 `Range`
+    #[inline]
+    pub const fn end(&self) -> T {
+        self.range.end
+    }
+}
+
+impl<T: Ord + Copy + Debug + Default> RangeInfo for UnitRange<T> {
+    type Kind = ();
+    type Type = T;
+
+    fn range(&self) -> Range<T> {
+        self.range.clone()
+    }
+
+    fn kind(&self) {}
+
+    fn overwritable(&self) -> bool {
+        self.overwritable
+    }
+
+    fn clone_with_range(&self, range: Range<T>) -> Self {
+        Self {
+            range,
+            overwritable: self.overwritable,
+        }
+    }
+}
+
+impl<T: Ord + Copy + Debug + Default> LockableRangeInfo for UnitRange<T> {
+    fn set_overwritable(&mut self, overwritable: bool) {
+        self.overwritable = overwritable;
+    }
+}
+
+/// `(区间, kind)` 元组可以直接作为元素使用，总是可被覆盖
+impl<T, K> RangeInfo for (Range<T>, K)
+where
+    T: Ord + Copy + Debug + Default,
+    K: Debug + Eq + Clone + Default,
+{
+    type Kind = K;
+    type Type = T;
+
+    fn range(&self) -> Range<T> {
+        self.0.clone()
+    }
+
+    fn kind(&self) -> K {
+        self.1.clone()
+    }
+
+    fn overwritable(&self) -> bool {
+        true
+    }
+
+    fn clone_with_range(&self, range: Range<T>) -> Self {
+        (range, self.1.clone())
+    }
+}
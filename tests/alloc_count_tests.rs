@@ -0,0 +1,114 @@
+#![cfg(feature = "alloc")]
+#![cfg(any(windows, unix))]
+
+mod common;
+use common::*;
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+// 统计当前线程分配次数的全局分配器
+struct CountingAlloc;
+
+std::thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|c| c.set(c.get() + 1));
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.with(|c| c.set(c.get() + 1));
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+fn allocations() -> usize {
+    ALLOCATIONS.with(|c| c.get())
+}
+
+#[test]
+fn alloc_merge_remove_does_not_allocate() {
+    let mut set: Vec<TestRange<u32>> = (0..1000)
+        .map(|i| TestRange::new(i * 10..i * 10 + 5, true))
+        .collect();
+
+    // 落在空隙中的删除、裁剪和整段删除都只改动受影响的窗口
+    let before = allocations();
+    for i in 0..1000 {
+        set.merge_remove(i * 10 + 6..i * 10 + 9).unwrap();
+    }
+    for i in 0..500 {
+        set.merge_remove(i * 10 + 3..i * 10 + 7).unwrap();
+    }
+    set.merge_remove(5000..7000).unwrap();
+    assert_eq!(allocations(), before);
+
+    // 分割只在容量不足时才会增长
+    set.reserve(10);
+    let before = allocations();
+    for i in 0..10 {
+        set.merge_remove(i * 10 + 1..i * 10 + 2).unwrap();
+    }
+    assert_eq!(allocations(), before);
+    assert_eq!(set.validate(), Ok(()));
+}
+
+#[test]
+fn alloc_merge_remove_matches_heapless() {
+    let mut temp = temp_buffer();
+    let items = [
+        TestRangeWithKind::new(0..10, 1, true),
+        TestRangeWithKind::new(10..20, 2, true),
+        TestRangeWithKind::new(25..40, 1, true),
+        TestRangeWithKind::new(50..60, 3, true),
+    ];
+    let removals = [5..15, 0..1, 22..26, 30..35, 39..55, 59..100, 12..13];
+
+    let mut alloc_set = Vec::new();
+    let mut heapless_set = heapless::Vec::<_, 16>::new();
+    alloc_set.merge_extend(items.clone()).unwrap();
+    heapless_set.merge_extend(items, &mut temp).unwrap();
+
+    for range in removals {
+        alloc_set.merge_remove(range.clone()).unwrap();
+        heapless_set.merge_remove(range, &mut temp).unwrap();
+        assert_eq!(alloc_set.as_slice(), heapless_set.as_slice());
+    }
+}
+
+#[test]
+fn alloc_duplicate_merge_add_is_skipped() {
+    let mut set: Vec<TestRangeWithKind<u32, u8>> = Vec::new();
+    set.merge_add(TestRangeWithKind::new(0..10, 1, true))
+        .unwrap();
+    set.merge_add(TestRangeWithKind::new(20..30, 2, false))
+        .unwrap();
+    let snapshot = set.clone();
+    let storage = set.as_ptr();
+
+    // 完全相同的元素重复添加：不分配、不移动存储，内容不变
+    let before = allocations();
+    for _ in 0..100 {
+        set.merge_add(TestRangeWithKind::new(20..30, 2, false))
+            .unwrap();
+    }
+    assert_eq!(allocations(), before);
+    assert_eq!(set.as_ptr(), storage);
+    assert_eq!(set, snapshot);
+
+    // kind 不同则照常覆盖
+    set.merge_add(TestRangeWithKind::new(0..10, 3, true))
+        .unwrap();
+    assert_eq!(set[0].kind, 3);
+}
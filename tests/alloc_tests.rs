@@ -218,3 +218,465 @@ fn alloc_negative_ranges() {
     assert!(!set.test_contains_point(-60));
     assert!(!set.test_contains_point(10));
 }
+
+#[test]
+fn alloc_count_by_kind_groups_consecutive_kinds() {
+    let mut set = Vec::<TestRangeWithKind<u64, char>>::new();
+    set.merge_extend([
+        TestRangeWithKind::new(0..10, 'a', true),
+        TestRangeWithKind::new(10..30, 'b', true),
+        TestRangeWithKind::new(35..40, 'b', true),
+    ])
+    .unwrap();
+
+    let counts: Vec<_> = set.count_by_kind().collect();
+    assert_eq!(counts, [('a', 1, 10), ('b', 2, 25)]);
+}
+
+#[test]
+fn alloc_conflicts_for_lists_all_conflicts() {
+    let mut set = Vec::<TestRangeWithKind<i32, i32>>::new();
+    set.merge_extend([
+        TestRangeWithKind::new(0..10, 1, false),
+        TestRangeWithKind::new(10..20, 2, true),
+        TestRangeWithKind::new(20..30, 3, false),
+        TestRangeWithKind::new(30..40, 4, false),
+    ])
+    .unwrap();
+    let before = set.clone();
+
+    let candidate = TestRangeWithKind::new(5..35, 4, true);
+    let conflicts = set.conflicts_for(&candidate);
+
+    // 可覆盖元素和同 kind 元素不算冲突
+    assert_eq!(conflicts, [&before[0], &before[2]]);
+    assert_eq!(set, before);
+}
+
+#[test]
+fn alloc_preview_add_matches_actual_apply() {
+    let mut set = Vec::<TestRangeWithKind<i32, i32>>::new();
+    set.merge_add(TestRangeWithKind::new(r(0, 10), 1, false))
+        .unwrap();
+    let before = set.clone();
+
+    let info = TestRangeWithKind::new(r(10, 20), 1, false);
+    let preview = set.preview_add(info.clone()).unwrap();
+    assert_eq!(set, before);
+
+    set.merge_add(info).unwrap();
+    assert_eq!(set, preview);
+
+    // 冲突时预览同样报告错误
+    let result = set.preview_add(TestRangeWithKind::new(r(5, 15), 2, true));
+    assert!(matches!(result, Err(RangeError::Conflict { .. })));
+}
+
+std::thread_local! {
+    static RANGE_CALLS: core::cell::Cell<usize> = const { core::cell::Cell::new(0) };
+    static CLONE_WITH_RANGE_CALLS: core::cell::Cell<usize> = const { core::cell::Cell::new(0) };
+    static CLONE_CALLS: core::cell::Cell<usize> = const { core::cell::Cell::new(0) };
+}
+
+// 统计 range()、clone_with_range() 和 clone() 调用次数的区间实现
+#[derive(Debug, Default, PartialEq, Eq)]
+struct CountingRange {
+    start: u32,
+    end: u32,
+    kind: u8,
+}
+
+impl Clone for CountingRange {
+    fn clone(&self) -> Self {
+        CLONE_CALLS.with(|c| c.set(c.get() + 1));
+        Self {
+            start: self.start,
+            end: self.end,
+            kind: self.kind,
+        }
+    }
+}
+
+impl RangeInfo for CountingRange {
+    type Kind = u8;
+    type Type = u32;
+
+    fn range(&self) -> core::ops::Range<u32> {
+        RANGE_CALLS.with(|c| c.set(c.get() + 1));
+        self.start..self.end
+    }
+
+    fn kind(&self) -> u8 {
+        self.kind
+    }
+
+    fn overwritable(&self) -> bool {
+        false
+    }
+
+    fn clone_with_range(&self, range: core::ops::Range<u32>) -> Self {
+        CLONE_WITH_RANGE_CALLS.with(|c| c.set(c.get() + 1));
+        Self {
+            start: range.start,
+            end: range.end,
+            kind: self.kind,
+        }
+    }
+}
+
+#[test]
+fn alloc_conflict_scan_stops_past_new_range() {
+    let set: Vec<CountingRange> = (0..1000)
+        .map(|i| CountingRange {
+            start: i * 10,
+            end: i * 10 + 5,
+            kind: 1,
+        })
+        .collect();
+
+    let candidate = CountingRange {
+        start: 100,
+        end: 130,
+        kind: 2,
+    };
+
+    RANGE_CALLS.with(|c| c.set(0));
+    let conflicts = set.conflicts_for(&candidate);
+    let calls = RANGE_CALLS.with(|c| c.get());
+
+    assert_eq!(conflicts.len(), 3);
+    // 二分查找加上少量重叠元素，远少于元素总数
+    assert!(calls < 64, "range() called {calls} times");
+}
+
+#[test]
+fn alloc_split_hook_defaults_to_unchanged() {
+    let mut set = Vec::<TestRangeWithKind<i32, i32>>::new();
+    set.merge_add(TestRangeWithKind::new(r(0, 30), 1, true))
+        .unwrap();
+
+    // 默认 merge_add 不修改片段
+    set.merge_add(TestRangeWithKind::new(r(10, 20), 2, true))
+        .unwrap();
+    assert!(set.iter().all(|e| e.overwritable));
+
+    set.merge_add_with_split_hook(TestRangeWithKind::new(r(0, 5), 3, true), |part| {
+        part.overwritable = false
+    })
+    .unwrap();
+    assert_eq!(set[1], TestRangeWithKind::new(r(5, 10), 1, false));
+}
+
+#[test]
+fn alloc_compact_keeps_contents() {
+    let mut set = Vec::<TestRange<i32>>::new();
+    for i in 0..100 {
+        set.merge_add(TestRange::new(r(i * 10, i * 10 + 5), true))
+            .unwrap();
+    }
+    set.merge_remove(r(50, 1000)).unwrap();
+    let before = set.clone();
+
+    let freed = set.compact();
+    assert!(freed > 0);
+    assert_eq!(set.len(), before.len());
+    assert_eq!(set.as_slice(), before.as_slice());
+
+    // 再次压缩没有可释放的容量
+    assert_eq!(set.compact(), 0);
+}
+
+#[test]
+fn alloc_snapshot_into_reuses_allocation() {
+    let mut set = Vec::<TestRange<i32>>::new();
+    set.merge_extend([
+        TestRange::new(r(0, 5), true),
+        TestRange::new(r(10, 15), true),
+    ])
+    .unwrap();
+
+    let mut dst = Vec::with_capacity(8);
+    let capacity = dst.capacity();
+    let ptr = dst.as_ptr();
+
+    for i in 0..10 {
+        set.merge_add(TestRange::new(r(20 + i, 21 + i), true))
+            .unwrap();
+        set.snapshot_into(&mut dst);
+        assert_eq!(dst, set);
+        assert_eq!(dst.capacity(), capacity);
+        assert_eq!(dst.as_ptr(), ptr);
+    }
+}
+
+#[test]
+fn alloc_pairs_yields_adjacent_elements() {
+    let mut set = Vec::<TestRange<i32>>::new();
+    set.merge_extend([
+        TestRange::new(r(0, 1), true),
+        TestRange::new(r(2, 3), true),
+        TestRange::new(r(4, 5), true),
+    ])
+    .unwrap();
+
+    let gaps: Vec<_> = set
+        .pairs()
+        .map(|(a, b)| a.range().end..b.range().start)
+        .collect();
+    assert_eq!(gaps, [r(1, 2), r(3, 4)]);
+}
+
+#[test]
+fn alloc_btreemap_round_trip() {
+    use std::collections::BTreeMap;
+
+    let map = BTreeMap::from([(0, 10), (5, 15), (20, 30), (30, 35)]);
+    let set =
+        Vec::<TestRange<i32>>::from_btreemap(map, |range| TestRange::new(range, true)).unwrap();
+
+    // 重叠与相接的区间被重新合并
+    assert_eq!(
+        set,
+        [
+            TestRange::new(r(0, 15), true),
+            TestRange::new(r(20, 35), true)
+        ]
+    );
+
+    let exported = set.to_btreemap();
+    assert_eq!(exported, BTreeMap::from([(0, 15), (20, 35)]));
+
+    let again = Vec::<TestRange<i32>>::from_btreemap(exported, |range| TestRange::new(range, true))
+        .unwrap();
+    assert_eq!(again, set);
+}
+
+#[test]
+fn alloc_merge_add_builds_merged_element_once() {
+    let mut set: Vec<CountingRange> = (0..10_000)
+        .map(|i| CountingRange {
+            start: i * 10,
+            end: i * 10 + 5,
+            kind: 1,
+        })
+        .collect();
+
+    // 与左右两个同 kind 元素合并，不克隆集合中的其他元素
+    CLONE_WITH_RANGE_CALLS.with(|c| c.set(0));
+    CLONE_CALLS.with(|c| c.set(0));
+    set.merge_add(CountingRange {
+        start: 50_002,
+        end: 50_012,
+        kind: 1,
+    })
+    .unwrap();
+    assert_eq!(CLONE_WITH_RANGE_CALLS.with(|c| c.get()), 1);
+    assert_eq!(CLONE_CALLS.with(|c| c.get()), 0);
+    assert_eq!(set.len(), 9_999);
+
+    // 不需要合并时不构造新元素
+    CLONE_WITH_RANGE_CALLS.with(|c| c.set(0));
+    CLONE_CALLS.with(|c| c.set(0));
+    set.merge_add(CountingRange {
+        start: 100_006,
+        end: 100_008,
+        kind: 1,
+    })
+    .unwrap();
+    assert_eq!(CLONE_WITH_RANGE_CALLS.with(|c| c.get()), 0);
+    assert_eq!(CLONE_CALLS.with(|c| c.get()), 0);
+    assert_eq!(set.len(), 10_000);
+}
+
+// 简单的线性同余随机数，保证测试可复现
+fn lcg(state: &mut u64) -> u64 {
+    *state = state
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    *state >> 33
+}
+
+#[test]
+fn alloc_merge_extend_sorted_matches_merge_extend() {
+    let mut seed = 0x5eed;
+    for _ in 0..2000 {
+        let mut base = Vec::new();
+        for _ in 0..8 {
+            let start = (lcg(&mut seed) % 100) as i32;
+            let len = (lcg(&mut seed) % 15) as i32;
+            let kind = (lcg(&mut seed) % 3) as i32;
+            let _ = base.merge_add(TestRangeWithKind::new(
+                start..start + len,
+                kind,
+                !lcg(&mut seed).is_multiple_of(4),
+            ));
+        }
+
+        // 乱序输入，不同 kind 之间可能重叠，结果必须完全一致（包括错误）
+        let incoming: Vec<_> = (0..10)
+            .map(|_| {
+                let start = (lcg(&mut seed) % 100) as i32;
+                let len = (lcg(&mut seed) % 15) as i32;
+                let kind = (lcg(&mut seed) % 3) as i32;
+                TestRangeWithKind::new(start..start + len, kind, !lcg(&mut seed).is_multiple_of(4))
+            })
+            .collect();
+
+        let mut expected = base.clone();
+        let expected_result = expected.merge_extend(incoming.clone());
+        let mut actual = base.clone();
+        let actual_result = actual.merge_extend_sorted(incoming);
+        assert_eq!(actual_result, expected_result);
+        assert_eq!(actual, expected);
+    }
+}
+
+#[test]
+fn alloc_merge_extend_sorted_keeps_order_for_overlapping_kinds() {
+    let incoming = [
+        TestRangeWithKind::new(r(5, 8), 2, true),
+        TestRangeWithKind::new(r(0, 10), 1, true),
+    ];
+
+    let mut expected = Vec::new();
+    expected.merge_extend(incoming.clone()).unwrap();
+    let mut actual = Vec::new();
+    actual.merge_extend_sorted(incoming).unwrap();
+    assert_eq!(actual, expected);
+    assert_eq!(actual, [TestRangeWithKind::new(r(0, 10), 1, true)]);
+}
+
+#[test]
+fn alloc_merge_extend_sorted_does_not_unlock_existing_elements() {
+    let mut base = Vec::new();
+    base.merge_extend([
+        TestRangeWithKind::new(r(5, 12), 1, false),
+        TestRangeWithKind::new(r(20, 25), 1, true),
+    ])
+    .unwrap();
+    let incoming = [
+        TestRangeWithKind::new(r(10, 12), 0, true),
+        TestRangeWithKind::new(r(38, 39), 0, true),
+        TestRangeWithKind::new(r(0, 7), 1, true),
+        TestRangeWithKind::new(r(30, 36), 1, true),
+    ];
+
+    // 按原顺序 10..12 先与不可覆盖的 5..12 冲突，排序后先合并 0..7 也不能绕过锁定
+    let mut expected = base.clone();
+    let expected_result = expected.merge_extend(incoming.clone());
+    assert!(matches!(expected_result, Err(RangeError::Conflict { .. })));
+    let mut actual = base.clone();
+    assert_eq!(actual.merge_extend_sorted(incoming), expected_result);
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn alloc_merge_extend_sorted_handles_reverse_input() {
+    let reversed: Vec<_> = (0..1000)
+        .rev()
+        .map(|i| TestRange::new(i * 10..i * 10 + 5, true))
+        .collect();
+
+    let mut expected = Vec::new();
+    expected.merge_extend(reversed.clone()).unwrap();
+    let mut actual = Vec::new();
+    actual.merge_extend_sorted(reversed).unwrap();
+    assert_eq!(actual, expected);
+    assert_eq!(actual.len(), 1000);
+}
+
+// 通过 trait 调用，泛型代码也能预留空间
+fn reserve_for<S: RangeVecAllocOps<TestRange<u64>>>(
+    set: &mut S,
+    additional: usize,
+) -> Result<(), std::collections::TryReserveError> {
+    RangeVecAllocOps::try_reserve(set, additional)
+}
+
+#[test]
+fn alloc_try_reserve_propagates_errors() {
+    let mut set = Vec::new();
+    set.merge_add(TestRange::new(0..10, true)).unwrap();
+
+    assert!(reserve_for(&mut set, 16).is_ok());
+    assert!(set.capacity() >= 17);
+
+    // 无法满足的预留返回错误而不是中止，集合保持不变
+    assert!(reserve_for(&mut set, usize::MAX).is_err());
+    assert_eq!(set, [TestRange::new(0..10, true)]);
+    set.merge_add(TestRange::new(20..30, true)).unwrap();
+    assert_eq!(set.len(), 2);
+}
+
+#[test]
+fn alloc_merge_add_notifying_reports_each_outcome() {
+    let mut set: Vec<TestRangeWithKind<i32, i32>> = Vec::new();
+
+    assert_eq!(
+        set.merge_add_notifying(TestRangeWithKind::new(r(0, 10), 1, true)),
+        Ok(AddOutcome::Inserted)
+    );
+    // 相接的同 kind 区间合并
+    assert_eq!(
+        set.merge_add_notifying(TestRangeWithKind::new(r(10, 20), 1, true)),
+        Ok(AddOutcome::MergedSameKind)
+    );
+    assert_eq!(
+        set.merge_add_notifying(TestRangeWithKind::new(r(30, 40), 2, true)),
+        Ok(AddOutcome::Inserted)
+    );
+
+    // 覆盖不同 kind 时列出被覆盖的部分，即使同时与同 kind 元素合并
+    assert_eq!(
+        set.merge_add_notifying(TestRangeWithKind::new(r(15, 35), 2, true)),
+        Ok(AddOutcome::Overrode(vec![TestRangeWithKind::new(
+            r(15, 20),
+            1,
+            true
+        )]))
+    );
+    assert_eq!(
+        set,
+        [
+            TestRangeWithKind::new(r(0, 15), 1, true),
+            TestRangeWithKind::new(r(15, 40), 2, true),
+        ]
+    );
+
+    // 冲突时原样返回错误
+    set.merge_add(TestRangeWithKind::new(r(50, 60), 3, false))
+        .unwrap();
+    assert!(matches!(
+        set.merge_add_notifying(TestRangeWithKind::new(r(55, 65), 1, true)),
+        Err(RangeError::Conflict { .. })
+    ));
+}
+
+#[test]
+fn alloc_replace_all_swaps_contents() {
+    let mut set = Vec::<TestRangeWithKind<i32, i32>>::new();
+    set.merge_add(TestRangeWithKind::new(r(0, 10), 1, true))
+        .unwrap();
+
+    let next = vec![
+        TestRangeWithKind::new(r(20, 30), 2, true),
+        TestRangeWithKind::new(r(30, 40), 3, false),
+    ];
+    set.replace_all(next.clone());
+    assert_eq!(set.as_slice(), next.as_slice());
+    assert!(!set.contains_point(5));
+
+    set.replace_all(Vec::new());
+    assert!(set.is_empty());
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic]
+fn alloc_replace_all_rejects_unsorted_in_debug() {
+    let mut set = Vec::<TestRangeWithKind<i32, i32>>::new();
+    set.replace_all(vec![
+        TestRangeWithKind::new(r(20, 30), 1, true),
+        TestRangeWithKind::new(r(0, 10), 2, true),
+    ]);
+}
@@ -0,0 +1,252 @@
+#![cfg(any(windows, unix))]
+
+mod common;
+use common::*;
+
+fn used(range: core::ops::Range<u32>) -> TestRange<u32> {
+    TestRange::new(range, false)
+}
+
+fn arena() -> heapless::Vec<TestRange<u32>, 16> {
+    // 空隙：[0,10) [20,25) [30,45) [60,100)
+    let mut set = heapless::Vec::new();
+    let mut temp = temp_buffer();
+    set.merge_extend([used(10..20), used(25..30), used(45..60)], &mut temp)
+        .unwrap();
+    set
+}
+
+#[test]
+fn gaps_within_clips_to_bounds() {
+    let set = arena();
+    let gaps: std::vec::Vec<_> = set.gaps_within(5..50).collect();
+    assert_eq!(gaps, [5..10, 20..25, 30..45]);
+
+    let gaps: std::vec::Vec<_> = set.gaps_within(12..18).collect();
+    assert!(gaps.is_empty());
+
+    let gaps: std::vec::Vec<_> = set.gaps_within(0..100).collect();
+    assert_eq!(gaps, [0..10, 20..25, 30..45, 60..100]);
+}
+
+#[test]
+fn first_fit_picks_lowest_gap() {
+    let set = arena();
+
+    assert_eq!(set.first_fit(3, 0..100), Some(0..3));
+    assert_eq!(set.first_fit(12, 0..100), Some(30..42));
+    // 恰好填满
+    assert_eq!(set.first_fit(5, 15..100), Some(20..25));
+    // 无法容纳
+    assert_eq!(set.first_fit(41, 0..100), None);
+    assert_eq!(set.first_fit(20, 0..60), None);
+}
+
+#[test]
+fn best_fit_minimizes_fragmentation() {
+    let set = arena();
+
+    // 首次适配选最低的 [0,10)，最佳适配选最小的 [20,25)
+    assert_eq!(set.first_fit(4, 0..100), Some(0..4));
+    assert_eq!(set.best_fit(4, 0..100), Some(20..24));
+
+    // 只有 [30,45) 和 [60,100) 足够大，选较小的
+    assert_eq!(set.first_fit(11, 0..100), Some(30..41));
+    assert_eq!(set.best_fit(11, 0..100), Some(30..41));
+    assert_eq!(set.best_fit(16, 0..100), Some(60..76));
+    assert_eq!(set.best_fit(41, 0..100), None);
+}
+
+#[test]
+fn best_fit_ties_pick_lowest_start() {
+    let mut set = heapless::Vec::<TestRange<u32>, 16>::new();
+    let mut temp = temp_buffer();
+    // 空隙：[0,5) [10,15) [20,30)
+    set.merge_extend([used(5..10), used(15..20)], &mut temp)
+        .unwrap();
+
+    assert_eq!(set.best_fit(5, 0..30), Some(0..5));
+}
+
+#[test]
+fn allocate_until_arena_is_full() {
+    let mut set = heapless::Vec::<TestRangeWithKind<u32, u32>, 16>::new();
+    let mut temp = temp_buffer();
+
+    for i in 0..4 {
+        let range = set
+            .allocate(
+                25,
+                0..100,
+                |range| TestRangeWithKind::new(range, i, false),
+                &mut temp,
+            )
+            .unwrap();
+        assert_eq!(range, i * 25..i * 25 + 25);
+    }
+
+    let result = set.allocate(
+        1,
+        0..100,
+        |range| TestRangeWithKind::new(range, 9, false),
+        &mut temp,
+    );
+    assert_eq!(result, Err(RangeError::NoFit));
+    assert_eq!(set.len(), 4);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn alloc_allocate_fills_gaps() {
+    let mut set = std::vec::Vec::<TestRange<u32>>::new();
+    set.merge_add(used(10..20)).unwrap();
+
+    assert_eq!(set.allocate(10, 0..30, used), Ok(0..10));
+    assert_eq!(set.allocate(10, 0..30, used), Ok(20..30));
+    assert_eq!(set.allocate(10, 0..30, used), Err(RangeError::NoFit));
+    assert_eq!(set, [used(0..30)]);
+}
+
+#[test]
+fn deallocate_rejects_partial_and_double_free() {
+    let mut set = arena();
+    let mut temp = temp_buffer();
+    let before = set.clone();
+
+    // 部分释放：[15,22) 跨越空隙 [20,25)
+    assert_eq!(
+        set.deallocate(15..22, &mut temp),
+        Err(RangeError::NotAllocated)
+    );
+    assert_eq!(set, before);
+
+    set.deallocate(10..20, &mut temp).unwrap();
+    assert!(!set.contains_point(10));
+
+    // 重复释放
+    assert_eq!(
+        set.deallocate(10..20, &mut temp),
+        Err(RangeError::NotAllocated)
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn alloc_deallocate_rejects_double_free() {
+    let mut set = std::vec::Vec::<TestRange<u32>>::new();
+    let range = set.allocate(8, 0..64, used).unwrap();
+
+    set.deallocate(range.clone()).unwrap();
+    assert!(set.is_empty());
+    assert_eq!(set.deallocate(range), Err(RangeError::NotAllocated));
+}
+
+fn owned(range: core::ops::Range<u32>, kind: u32) -> TestRangeWithKind<u32, u32> {
+    TestRangeWithKind::new(range, kind, false)
+}
+
+#[test]
+fn resize_element_grows_shrinks_and_conflicts() {
+    let mut set = heapless::Vec::<TestRangeWithKind<u32, u32>, 16>::new();
+    let mut temp = temp_buffer();
+    set.merge_extend(
+        [owned(0..10, 1), owned(20..30, 1), owned(40..50, 2)],
+        &mut temp,
+    )
+    .unwrap();
+
+    // 增长到空隙中，并与相邻同 kind 元素合并
+    set.resize_element(0..10, 20, &mut temp).unwrap();
+    assert_eq!(set.as_slice(), &[owned(0..30, 1), owned(40..50, 2)]);
+
+    // 增长撞上不同 kind 的不可覆盖元素
+    let result = set.resize_element(0..30, 45, &mut temp);
+    assert!(matches!(result, Err(RangeError::Conflict { .. })));
+    assert_eq!(set.as_slice(), &[owned(0..30, 1), owned(40..50, 2)]);
+
+    // 缩小
+    set.resize_element(40..50, 42, &mut temp).unwrap();
+    assert_eq!(set.as_slice(), &[owned(0..30, 1), owned(40..42, 2)]);
+
+    // 非精确匹配
+    assert_eq!(
+        set.resize_element(0..10, 5, &mut temp),
+        Err(RangeError::NotAllocated)
+    );
+}
+
+#[test]
+fn resize_element_rejects_end_before_start() {
+    let mut set = heapless::Vec::<TestRangeWithKind<u32, u32>, 16>::new();
+    let mut temp = temp_buffer();
+    set.merge_extend([owned(0..10, 1), owned(20..30, 2)], &mut temp)
+        .unwrap();
+    let before = set.clone();
+
+    // 终点落在元素起点之前或等于起点，不能影响其他元素
+    assert_eq!(
+        set.resize_element(20..30, 5, &mut temp),
+        Err(RangeError::EmptyRange)
+    );
+    assert_eq!(
+        set.resize_element(20..30, 20, &mut temp),
+        Err(RangeError::EmptyRange)
+    );
+    assert_eq!(set, before);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn alloc_resize_element_rejects_end_before_start() {
+    let mut set = vec![owned(0..10, 1), owned(20..30, 2)];
+    assert_eq!(set.resize_element(20..30, 5), Err(RangeError::EmptyRange));
+    assert_eq!(set, [owned(0..10, 1), owned(20..30, 2)]);
+
+    set.resize_element(20..30, 21).unwrap();
+    assert_eq!(set, [owned(0..10, 1), owned(20..21, 2)]);
+}
+
+#[test]
+fn relocate_into_gap_and_occupied_region() {
+    let mut set = heapless::Vec::<TestRangeWithKind<u32, u32>, 16>::new();
+    let mut temp = temp_buffer();
+    set.merge_extend(
+        [owned(0..10, 1), owned(10..15, 2), owned(40..50, 3)],
+        &mut temp,
+    )
+    .unwrap();
+
+    // 移动到空隙中，保留各段的 kind
+    set.relocate(5..15, 20, &mut temp).unwrap();
+    assert_eq!(
+        set.as_slice(),
+        &[
+            owned(0..5, 1),
+            owned(20..25, 1),
+            owned(25..30, 2),
+            owned(40..50, 3)
+        ]
+    );
+
+    // 移动到被不可覆盖元素占用的区域，恢复原状态
+    let before = set.clone();
+    let result = set.relocate(20..30, 35, &mut temp);
+    assert!(matches!(result, Err(RangeError::Conflict { .. })));
+    assert_eq!(set, before);
+
+    // 源区间未被完全覆盖
+    assert_eq!(
+        set.relocate(0..10, 60, &mut temp),
+        Err(RangeError::NotAllocated)
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn alloc_relocate_into_gap() {
+    let mut set = std::vec::Vec::<TestRange<u32>>::new();
+    set.merge_add(used(0..10)).unwrap();
+
+    set.relocate(0..10, 100).unwrap();
+    assert_eq!(set, [used(100..110)]);
+}
@@ -125,6 +125,53 @@ fn test_single_point_ranges() {
     assert_eq!(set.as_slice()[0].range(), (6..7));
 }
 
+#[test]
+fn test_single_point_removal_permutations() {
+    // 元素 [5,7) 上删除各种单点/边界区间，剩余部分不能出现 [7,7) 之类的空片段
+    let cases = [
+        (5..6, [None, Some(6..7)]),
+        (6..7, [Some(5..6), None]),
+        (5..7, [None, None]),
+        (4..6, [None, Some(6..7)]),
+        (6..8, [Some(5..6), None]),
+    ];
+    for (remove, expected) in cases {
+        let elem = TestRange::new(5..7, true);
+        let parts = split_range(&elem, &remove).map(|p| p.map(|p| p.range));
+        assert_eq!(parts, expected, "split_range {remove:?}");
+
+        let remaining = || expected.iter().flatten().cloned();
+        let mut set = heapless::Vec::<TestRange<i32>, 4>::new();
+        set.test_add(elem.clone()).unwrap();
+        set.test_remove(remove.clone()).unwrap();
+        assert!(set.iter().map(|e| e.range.clone()).eq(remaining()));
+        assert_eq!(set.validate(), Ok(()));
+
+        #[cfg(feature = "alloc")]
+        {
+            let mut alloc_set = vec![elem];
+            alloc_set.merge_remove(remove.clone()).unwrap();
+            assert!(alloc_set.iter().map(|e| e.range.clone()).eq(remaining()));
+        }
+    }
+
+    // 相邻的不同 kind 单点元素 [5,6) 与 [6,7) 互不影响
+    let mut temp = temp_buffer();
+    let mut set = heapless::Vec::<TestRangeWithKind<i32, i32>, 4>::new();
+    let (a, b) = (
+        TestRangeWithKind::new(5..6, 1, true),
+        TestRangeWithKind::new(6..7, 2, true),
+    );
+    for (remove, expected) in [(5..6, &b), (6..7, &a)] {
+        set.clear();
+        set.merge_extend([a.clone(), b.clone()], &mut temp).unwrap();
+        set.merge_remove(remove, &mut temp).unwrap();
+        assert_eq!(set.as_slice(), core::slice::from_ref(expected));
+    }
+    set.merge_remove(5..7, &mut temp).unwrap();
+    assert!(set.is_empty());
+}
+
 #[test]
 fn test_extreme_values() {
     let mut set = heapless::Vec::<TestRange<i32>, 128>::default();
@@ -144,3 +191,364 @@ fn test_extreme_values() {
     assert!(set.test_contains_point(i32::MAX - 1));
     assert!(!set.test_contains_point(i32::MAX));
 }
+
+#[test]
+fn test_add_to_end() {
+    let mut set = heapless::Vec::<TestRange<u32>, 8>::new();
+    let mut temp = temp_buffer();
+    let template = TestRange::new(0..0, true);
+
+    set.add_to_end(100, &template, &mut temp).unwrap();
+    assert_eq!(set.as_slice(), &[TestRange::new(100..u32::MAX, true)]);
+
+    // 半开区间：MAX - 1 被包含，MAX 本身永远不被包含
+    assert!(set.contains_point(u32::MAX - 1));
+    assert!(!set.contains_point(u32::MAX));
+    assert!(!set.contains_point(99));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_alloc_add_to_end() {
+    let mut set = Vec::<TestRange<i64>>::new();
+    set.add_to_end(-5, &TestRange::new(0..0, false)).unwrap();
+    assert!(set.contains_point(i64::MAX - 1));
+    assert!(!set.contains_point(i64::MAX));
+}
+
+#[test]
+fn test_checked_range_and_strict_add() {
+    assert_eq!(checked_range(3usize, 8), Some(3..8));
+    assert_eq!(checked_range(8usize, 8), None);
+    assert_eq!(checked_range(9usize, 8), None);
+
+    let mut set = heapless::Vec::<TestRange<usize>, 8>::new();
+    let mut temp = temp_buffer();
+
+    // 宽松模式：空区间被忽略
+    set.merge_add_checked(TestRange::new(8..8, true), false, &mut temp)
+        .unwrap();
+    assert!(set.is_empty());
+
+    // 严格模式：空区间和反向区间都报错
+    for range in [8..8, 9..8] {
+        assert_eq!(
+            set.merge_add_checked(TestRange::new(range, true), true, &mut temp),
+            Err(RangeError::EmptyRange)
+        );
+    }
+    assert!(set.is_empty());
+
+    set.merge_add_checked(TestRange::new(0..8, true), true, &mut temp)
+        .unwrap();
+    assert_eq!(set.len(), 1);
+}
+
+#[test]
+fn test_strict_add_rejects_empty_range() {
+    let mut set = heapless::Vec::<TestRange<i32>, 8>::new();
+    let mut temp = temp_buffer();
+
+    // 默认 merge_add 对空区间是 no-op
+    assert_eq!(
+        set.merge_add(TestRange::new(10..10, true), &mut temp),
+        Ok(())
+    );
+    assert!(set.is_empty());
+
+    assert_eq!(
+        set.merge_add_strict(TestRange::new(10..10, true), &mut temp),
+        Err(RangeError::EmptyRange)
+    );
+    assert!(set.is_empty());
+
+    set.merge_add_strict(TestRange::new(10..11, true), &mut temp)
+        .unwrap();
+    assert_eq!(set.len(), 1);
+}
+
+#[test]
+fn test_shift_by_saturates_or_errors() {
+    assert_eq!(saturating_add(250u8, 10), u8::MAX);
+    assert_eq!(saturating_sub(5u8, 10), 0);
+
+    let mut temp = temp_buffer();
+    let mut set = heapless::Vec::<TestRange<u8>, 8>::new();
+    set.merge_extend(
+        [
+            TestRange::new(10..20, true),
+            TestRange::new(200..u8::MAX, true),
+        ],
+        &mut temp,
+    )
+    .unwrap();
+    let before = set.clone();
+
+    // 检查版本：溢出时报错且不修改集合
+    assert_eq!(set.shift_by(10), Err(RangeError::Overflow));
+    assert_eq!(set, before);
+
+    // 饱和版本：终点停在 u8::MAX，而不是回绕
+    set.shift_by_saturating(10);
+    assert_eq!(
+        set.as_slice(),
+        &[
+            TestRange::new(20..30, true),
+            TestRange::new(210..u8::MAX, true)
+        ]
+    );
+
+    // 整体移出上界的元素被删除
+    set.shift_by_saturating(230);
+    assert_eq!(set.as_slice(), &[TestRange::new(250..u8::MAX, true)]);
+
+    set.shift_by(5).unwrap_err();
+    set.clear();
+    set.merge_add(TestRange::new(0..5, true), &mut temp)
+        .unwrap();
+    set.shift_by(5).unwrap();
+    assert_eq!(set.as_slice(), &[TestRange::new(5..10, true)]);
+}
+
+#[test]
+fn test_shift_by_checks_start_for_negative_delta() {
+    let mut temp = temp_buffer();
+    let mut set = heapless::Vec::<TestRange<i8>, 8>::new();
+    set.merge_add(TestRange::new(-120..-100, true), &mut temp)
+        .unwrap();
+    let before = set.clone();
+
+    // 起点越过 i8::MIN 时报错，而不是把元素截短
+    assert_eq!(set.shift_by(-20), Err(RangeError::Overflow));
+    assert_eq!(set, before);
+
+    set.shift_by(-8).unwrap();
+    assert_eq!(set.as_slice(), &[TestRange::new(-128..-108, true)]);
+}
+
+#[test]
+fn test_collapse_markers_around_deleted_range() {
+    let mut temp = temp_buffer();
+    let mut set = heapless::Vec::<TestRangeWithKind<u32, u8>, 4>::new();
+    set.merge_extend(
+        [
+            TestRangeWithKind::new(0..5, 1, true),
+            TestRangeWithKind::new(8..12, 2, true),
+            TestRangeWithKind::new(12..15, 1, true),
+            TestRangeWithKind::new(15..30, 3, false),
+        ],
+        &mut temp,
+    )
+    .unwrap();
+
+    // 删除 10..20：之前的不动，完全落在其中的被删除，跨越两端的被截断，之后的前移
+    set.collapse(10..20);
+    assert_eq!(
+        set.as_slice(),
+        &[
+            TestRangeWithKind::new(0..5, 1, true),
+            TestRangeWithKind::new(8..10, 2, true),
+            TestRangeWithKind::new(10..20, 3, false),
+        ]
+    );
+
+    // 删除后首尾相接的同 kind 元素被合并；容量已满时同样可以执行
+    set.merge_add(TestRangeWithKind::new(20..25, 2, true), &mut temp)
+        .unwrap();
+    assert!(set.is_full());
+    set.collapse(10..20);
+    assert_eq!(
+        set.as_slice(),
+        &[
+            TestRangeWithKind::new(0..5, 1, true),
+            TestRangeWithKind::new(8..15, 2, true),
+        ]
+    );
+
+    set.collapse(2..12);
+    assert_eq!(
+        set.as_slice(),
+        &[
+            TestRangeWithKind::new(0..2, 1, true),
+            TestRangeWithKind::new(2..5, 2, true),
+        ]
+    );
+    assert_eq!(set.validate(), Ok(()));
+}
+
+#[test]
+fn test_collapse_wide_signed_range() {
+    let mut temp = temp_buffer();
+    let mut set = heapless::Vec::<TestRangeWithKind<i8, u8>, 4>::new();
+    set.merge_extend(
+        [
+            TestRangeWithKind::new(-120..-110, 1, true),
+            TestRangeWithKind::new(-50..50, 2, true),
+            TestRangeWithKind::new(110..120, 3, true),
+        ],
+        &mut temp,
+    )
+    .unwrap();
+
+    // 删除区间的长度 200 超出 i8，之后的元素仍精确前移到 -90..-80
+    set.collapse(-100..100);
+    assert_eq!(
+        set.as_slice(),
+        &[
+            TestRangeWithKind::new(-120..-110, 1, true),
+            TestRangeWithKind::new(-90..-80, 3, true),
+        ]
+    );
+
+    // 右侧终点很大、起点为负的删除区间：先减 range.end 会溢出，需要换一种计算顺序
+    set.merge_add(TestRangeWithKind::new(100..127, 2, true), &mut temp)
+        .unwrap();
+    set.collapse(-85..-60);
+    assert_eq!(
+        set.as_slice(),
+        &[
+            TestRangeWithKind::new(-120..-110, 1, true),
+            TestRangeWithKind::new(-90..-85, 3, true),
+            TestRangeWithKind::new(75..102, 2, true),
+        ]
+    );
+}
+
+#[test]
+fn test_shift_after_splits_straddling_element() {
+    let mut temp = temp_buffer();
+    let mut set = heapless::Vec::<TestRangeWithKind<u8, u8>, 3>::new();
+    set.merge_extend(
+        [
+            TestRangeWithKind::new(0..5, 1, true),
+            TestRangeWithKind::new(10..20, 2, false),
+        ],
+        &mut temp,
+    )
+    .unwrap();
+
+    // 15 处插入 3：跨越 15 的元素被分割，右半部分后移，kind 与保护状态不变
+    set.shift_after(15, 3).unwrap();
+    assert_eq!(
+        set.as_slice(),
+        &[
+            TestRangeWithKind::new(0..5, 1, true),
+            TestRangeWithKind::new(10..15, 2, false),
+            TestRangeWithKind::new(18..23, 2, false),
+        ]
+    );
+    assert_eq!(set.validate(), Ok(()));
+
+    // 起点恰好等于 pivot 的元素整体后移，不分割
+    set.shift_after(18, 2).unwrap();
+    assert_eq!(set[2].range, 20..25);
+
+    // 需要分割但容量已满、坐标溢出时都不修改集合
+    let before = set.clone();
+    assert_eq!(set.shift_after(12, 1), Err(RangeError::Capacity));
+    assert_eq!(set.shift_after(0, 250), Err(RangeError::Overflow));
+    assert_eq!(set, before);
+
+    // pivot 之后没有元素或 delta 为 0 时什么都不做
+    set.shift_after(30, 5).unwrap();
+    set.shift_after(3, 0).unwrap();
+    assert_eq!(set, before);
+}
+
+#[test]
+fn test_float_ranges_with_total_order() {
+    let f = |v: f64| OrdF64::new(v).unwrap();
+    let mut set = heapless::Vec::<UnitRange<OrdF64>, 8>::new();
+    let mut temp = temp_buffer();
+
+    set.merge_add(UnitRange::new(f(0.5)..f(1.5)), &mut temp)
+        .unwrap();
+    set.merge_add(UnitRange::new(f(1.5)..f(2.25)), &mut temp)
+        .unwrap();
+    set.merge_add(UnitRange::new(f(-3.0)..f(-1.0)), &mut temp)
+        .unwrap();
+    assert_eq!(
+        set.as_slice(),
+        &[
+            UnitRange::new(f(-3.0)..f(-1.0)),
+            UnitRange::new(f(0.5)..f(2.25)),
+        ]
+    );
+
+    assert!(set.contains_point(f(2.0)));
+    assert!(!set.contains_point(f(2.25)));
+    assert!(!set.contains_point(f(0.0)));
+
+    set.merge_remove(f(1.0)..f(1.25), &mut temp).unwrap();
+    assert_eq!(set.len(), 3);
+    assert_eq!(set.validate(), Ok(()));
+
+    assert!(OrdF64::new(f64::NAN).is_none());
+    assert!(OrdF64(-0.0) < OrdF64(0.0));
+}
+
+#[test]
+fn test_coordinate_trait_on_i64() {
+    fn span<C: Coordinate>(range: core::ops::Range<C>) -> Option<i128> {
+        Some(range.end.to_i128()? - range.start.to_i128()?)
+    }
+
+    assert_eq!(<i64 as Coordinate>::ZERO, 0);
+    assert_eq!(Coordinate::checked_add(i64::MAX, 1), None);
+    assert_eq!(Coordinate::checked_sub(-5i64, 5), Some(-10));
+    assert_eq!(span(-10i64..i64::MAX), Some(i64::MAX as i128 + 10));
+    assert_eq!((-1i64).to_u128(), None);
+    assert_eq!(u128::MAX.to_i128(), None);
+
+    // 需要坐标运算的方法以 Coordinate 为约束
+    let mut temp = temp_buffer();
+    let mut set = heapless::Vec::<TestRange<i64>, 4>::new();
+    set.merge_add(TestRange::new(-20..-10, true), &mut temp)
+        .unwrap();
+    set.shift_by(i64::MAX - 10).unwrap();
+    assert_eq!(set[0].range, i64::MAX - 30..i64::MAX - 20);
+    assert_eq!(set.shift_by(30), Err(RangeError::Overflow));
+}
+
+#[test]
+fn test_char_ranges() {
+    // char 只满足 Ord + Copy，不实现 Coordinate，基本集合操作仍可用
+    // 区间为半开区间，'{' 紧跟在 'z' 之后
+    let mut temp = temp_buffer();
+    let mut set = heapless::Vec::<TestRangeWithKind<char, u8>, 8>::new();
+    set.merge_add(TestRangeWithKind::new('a'..'n', 1, true), &mut temp)
+        .unwrap();
+    set.merge_add(TestRangeWithKind::new('n'..'{', 1, true), &mut temp)
+        .unwrap();
+    set.merge_add(TestRangeWithKind::new('0'..':', 2, true), &mut temp)
+        .unwrap();
+    assert_eq!(
+        set.as_slice(),
+        &[
+            TestRangeWithKind::new('0'..':', 2, true),
+            TestRangeWithKind::new('a'..'{', 1, true),
+        ]
+    );
+
+    set.merge_remove('e'..'f', &mut temp).unwrap();
+    assert!(set.contains_point('d'));
+    assert!(!set.contains_point('e'));
+    assert!(set.contains_point('f'));
+    assert!(set.contains_point('7'));
+    assert!(set.contains_point('z'));
+    assert!(!set.contains_point('{'));
+    assert!(set.validate().is_ok());
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_alloc_char_ranges() {
+    let mut alloc_set = Vec::<TestRangeWithKind<char, u8>>::new();
+    alloc_set
+        .merge_add(TestRangeWithKind::new('α'..'ω', 1, true))
+        .unwrap();
+    alloc_set.merge_remove('β'..'γ').unwrap();
+    assert_eq!(alloc_set.len(), 2);
+    assert!(alloc_set.contains_point('α'));
+    assert!(!alloc_set.contains_point('β'));
+}
@@ -0,0 +1,151 @@
+#![cfg(any(windows, unix))]
+
+mod common;
+use common::*;
+
+fn kinded(range: core::ops::Range<i32>, kind: i32) -> TestRangeWithKind<i32, i32> {
+    TestRangeWithKind::new(range, kind, true)
+}
+
+#[test]
+fn predicted_len_matches_actual_outcome() {
+    let mut set = heapless::Vec::<TestRangeWithKind<i32, i32>, 32>::new();
+    let mut temp = temp_buffer();
+
+    let inserts = [
+        kinded(10..20, 1),
+        kinded(30..40, 1),
+        kinded(20..30, 1), // 合并成一个
+        kinded(15..25, 2), // 分割出三段
+        kinded(0..5, 2),
+        kinded(5..10, 2), // 与左右同 kind 区间合并
+        kinded(22..23, 3),
+        kinded(0..100, 4), // 覆盖全部
+        kinded(3..3, 5),   // 空区间
+    ];
+
+    for info in inserts {
+        let predicted = set.merge_add_predicted_len(&info);
+        set.merge_add(info, &mut temp).unwrap();
+        assert_eq!(predicted, set.len());
+    }
+}
+
+#[test]
+fn would_fit_detects_split_overflow() {
+    let mut set = heapless::Vec::<TestRangeWithKind<i32, i32>, 2>::new();
+    let mut temp = temp_buffer();
+    set.merge_add(kinded(0..100, 1), &mut temp).unwrap();
+
+    // 从中间插入不同 kind 会分割成三段，超出容量
+    let info = kinded(40..60, 2);
+    assert_eq!(set.merge_add_predicted_len(&info), 3);
+    assert!(!set.would_fit(&info));
+
+    // 覆盖尾部只会产生两段
+    let info = kinded(50..100, 2);
+    assert!(set.would_fit(&info));
+    set.merge_add(info, &mut temp).unwrap();
+    assert_eq!(set.len(), 2);
+}
+
+#[test]
+fn capacity_error_leaves_set_unchanged() {
+    let mut set = heapless::Vec::<TestRangeWithKind<i32, i32>, 3>::new();
+    let mut temp = temp_buffer();
+    set.merge_extend(
+        [kinded(0..10, 1), kinded(20..30, 1), kinded(40..100, 1)],
+        &mut temp,
+    )
+    .unwrap();
+    let before = set.clone();
+
+    // 分割最后一个区间会产生 5 个元素，超出容量
+    let result = set.merge_add(kinded(50..60, 2), &mut temp);
+    assert_eq!(result, Err(RangeError::Capacity));
+    assert_eq!(set, before);
+
+    // 删除操作同样不会丢失元素
+    let result = set.merge_remove(50..60, &mut temp);
+    assert_eq!(result, Err(RangeError::Capacity));
+    assert_eq!(set, before);
+}
+
+#[test]
+fn small_temp_buffer_leaves_set_unchanged() {
+    let mut set = heapless::Vec::<TestRangeWithKind<i32, i32>, 8>::new();
+    let mut temp = temp_buffer();
+    set.merge_extend(
+        [kinded(0..10, 1), kinded(20..30, 1), kinded(40..50, 1)],
+        &mut temp,
+    )
+    .unwrap();
+    let before = set.clone();
+
+    // 临时缓冲区只能容纳一个元素
+    let mut small = [0u8; core::mem::size_of::<TestRangeWithKind<i32, i32>>()];
+    let result = set.merge_add(kinded(60..70, 1), &mut small);
+    assert_eq!(result, Err(RangeError::Capacity));
+    assert_eq!(set, before);
+}
+
+#[test]
+fn range_set_aliases() {
+    let mut temp = temp_buffer();
+
+    // 未指定容量时使用 DEFAULT_CAPACITY
+    let mut set: RangeSet<TestRange<i32>> = RangeSet::new();
+    assert_eq!(set.capacity(), DEFAULT_CAPACITY);
+    set.merge_add(TestRange::new(0..10, true), &mut temp)
+        .unwrap();
+
+    let small: RangeSet<TestRange<i32>, 2> = RangeSet::new();
+    assert_eq!(small.capacity(), 2);
+
+    #[cfg(feature = "alloc")]
+    {
+        let mut growable: RangeSetVec<TestRange<i32>> = RangeSetVec::new();
+        growable.merge_add(TestRange::new(0..10, true)).unwrap();
+        assert_eq!(growable.as_slice(), set.as_slice());
+    }
+}
+
+// RangeSet::new() 是 const fn，可以直接初始化 static
+static EMPTY: RangeSet<UnitRange<u32>, 8> = RangeSet::new();
+static SHARED: std::sync::Mutex<RangeSet<UnitRange<u32>, 8>> =
+    std::sync::Mutex::new(RangeSet::new());
+
+#[test]
+fn range_set_in_static() {
+    assert!(EMPTY.is_empty());
+
+    let mut temp = temp_buffer();
+    let mut set = SHARED.lock().unwrap();
+    set.merge_add(UnitRange::new(0..10), &mut temp).unwrap();
+    set.merge_add(UnitRange::new(10..20), &mut temp).unwrap();
+    assert_eq!(set.as_slice(), &[UnitRange::new(0..20)]);
+}
+
+#[test]
+fn try_into_capacity_shrinks_storage() {
+    let mut temp = temp_buffer();
+    let mut set: RangeSet<TestRange<i32>, 64> = RangeSet::new();
+    set.merge_extend(
+        [
+            TestRange::new(0..5, true),
+            TestRange::new(10..15, true),
+            TestRange::new(20..25, true),
+        ],
+        &mut temp,
+    )
+    .unwrap();
+    let expected = set.clone();
+
+    let fitted: RangeSet<_, 4> = set.try_into_capacity().unwrap();
+    assert_eq!(fitted.as_slice(), expected.as_slice());
+
+    // 容量不足时原样返回
+    let back = fitted.try_into_capacity::<2>().unwrap_err();
+    assert_eq!(back.as_slice(), expected.as_slice());
+    assert_eq!(back.capacity(), 4);
+}
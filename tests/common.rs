@@ -5,6 +5,7 @@ use std::mem;
 use tinyvec::SliceVec;
 
 // 简单的区间信息实现，用于测试
+#[allow(unused)]
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct TestRange<T> {
     pub range: Range<T>,
@@ -22,6 +23,7 @@ impl<T: Default> Default for TestRange<T> {
     }
 }
 
+#[allow(unused)]
 impl<T> TestRange<T> {
     pub fn new(range: Range<T>, overwritable: bool) -> Self {
         Self {
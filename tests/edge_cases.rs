@@ -96,3 +96,184 @@ fn test_adjacent_but_different_kind() {
     assert_eq!(set.as_slice()[0].range(), (0..5));
     assert_eq!(set.as_slice()[1].range(), (5..15)); // 后两个应该合并
 }
+
+#[test]
+fn test_element_count_vs_total_len() {
+    let mut set = heapless::Vec::<TestRange<u32>, 8>::new();
+    let mut temp = temp_buffer();
+
+    // 只包含空区间的输入会被丢弃，集合仍为空
+    set.merge_extend(
+        [TestRange::new(5..5, true), TestRange::new(10..10, true)],
+        &mut temp,
+    )
+    .unwrap();
+    assert!(set.is_empty());
+    assert_eq!(set.element_count(), 0);
+    assert_eq!(set.total_len(), 0);
+
+    set.merge_extend(
+        [TestRange::new(0..10, true), TestRange::new(20..25, true)],
+        &mut temp,
+    )
+    .unwrap();
+    assert_eq!(set.element_count(), 2);
+    assert_eq!(set.element_count(), set.len());
+    assert_eq!(set.total_len(), 15);
+}
+
+#[test]
+fn test_covered_bytes_as_u64() {
+    let mut set = heapless::Vec::<TestRange<u32>, 8>::new();
+    let mut temp = temp_buffer();
+    set.merge_extend(
+        [
+            TestRange::new(0..4096, true),
+            TestRange::new(u32::MAX - 512..u32::MAX, true),
+        ],
+        &mut temp,
+    )
+    .unwrap();
+    let bytes: u64 = set.covered_bytes();
+    assert_eq!(bytes, 4096 + 512);
+    assert_eq!(bytes, u64::from(set.total_len()));
+
+    #[cfg(feature = "alloc")]
+    {
+        // total_len 在 u8 上恰好到上限，covered_bytes 以 u64 计算
+        let mut narrow = Vec::<TestRange<u8>>::new();
+        narrow.merge_add(TestRange::new(0..200, true)).unwrap();
+        narrow.merge_add(TestRange::new(200..255, true)).unwrap();
+        assert_eq!(narrow.covered_bytes(), 255);
+
+        let mut signed = Vec::<TestRange<i8>>::new();
+        signed
+            .merge_add(TestRange::new(i8::MIN..i8::MAX, true))
+            .unwrap();
+        assert_eq!(signed.covered_bytes(), 255);
+
+        let mut wide = Vec::<TestRange<u128>>::new();
+        wide.merge_add(TestRange::new(0..u128::MAX, true)).unwrap();
+        assert_eq!(wide.covered_bytes(), u64::MAX);
+    }
+}
+
+#[test]
+fn test_split_range_all_branches() {
+    let elem = TestRangeWithKind::new(10..20, 1, true);
+    let part = |r: core::ops::Range<i32>| Some(TestRangeWithKind::new(r, 1, true));
+
+    // (true, true)：分割范围在元素内部
+    assert_eq!(split_range(&elem, &(12..15)), [part(10..12), part(15..20)]);
+
+    // (true, false)：覆盖右端，包括右边界恰好对齐
+    assert_eq!(split_range(&elem, &(15..25)), [part(10..15), None]);
+    assert_eq!(split_range(&elem, &(15..20)), [part(10..15), None]);
+
+    // (false, true)：覆盖左端，包括左边界恰好对齐
+    assert_eq!(split_range(&elem, &(5..15)), [None, part(15..20)]);
+    assert_eq!(split_range(&elem, &(10..15)), [None, part(15..20)]);
+
+    // (false, false)：与元素完全相等或覆盖整个元素，两侧都为空
+    assert_eq!(split_range(&elem, &(10..20)), [None, None]);
+    assert_eq!(split_range(&elem, &(0..30)), [None, None]);
+}
+
+#[test]
+fn test_exclusive_add_checks_both_directions() {
+    let mut temp = temp_buffer();
+
+    // 已有元素不可覆盖：merge_add 与 merge_add_exclusive 都拒绝
+    let mut set = heapless::Vec::<TestRangeWithKind<i32, i32>, 16>::new();
+    set.merge_add(TestRangeWithKind::new(0..10, 1, false), &mut temp)
+        .unwrap();
+    let incoming = TestRangeWithKind::new(5..15, 2, true);
+    assert!(matches!(
+        set.merge_add(incoming.clone(), &mut temp),
+        Err(RangeError::Conflict { .. })
+    ));
+    assert!(matches!(
+        set.merge_add_exclusive(incoming, &mut temp),
+        Err(RangeError::Conflict { .. })
+    ));
+
+    // 新区间不可覆盖：已有元素可覆盖时 merge_add 允许，merge_add_exclusive 拒绝
+    let mut set = heapless::Vec::<TestRangeWithKind<i32, i32>, 16>::new();
+    set.merge_add(TestRangeWithKind::new(0..10, 1, true), &mut temp)
+        .unwrap();
+    let protected = TestRangeWithKind::new(5..15, 2, false);
+    assert_eq!(
+        set.merge_add_exclusive(protected.clone(), &mut temp),
+        Err(RangeError::Conflict {
+            new: protected.clone(),
+            existing: TestRangeWithKind::new(0..10, 1, true),
+        })
+    );
+    assert_eq!(set.len(), 1);
+
+    // 同 kind 重叠同样不允许
+    assert!(matches!(
+        set.merge_add_exclusive(TestRangeWithKind::new(5..15, 1, false), &mut temp),
+        Err(RangeError::Conflict { .. })
+    ));
+
+    // 落在空隙中（包括首尾相接）时正常添加
+    set.merge_add_exclusive(TestRangeWithKind::new(10..20, 2, false), &mut temp)
+        .unwrap();
+    assert_eq!(set.len(), 2);
+
+    #[cfg(feature = "alloc")]
+    {
+        let mut alloc_set = Vec::new();
+        alloc_set
+            .merge_add(TestRangeWithKind::new(0..10, 1, true))
+            .unwrap();
+        alloc_set.merge_add(protected.clone()).unwrap();
+        assert_eq!(alloc_set.len(), 2);
+        assert!(matches!(
+            alloc_set.merge_add_exclusive(TestRangeWithKind::new(12..18, 3, false)),
+            Err(RangeError::Conflict { .. })
+        ));
+    }
+}
+
+#[test]
+fn test_merge_add_within_rejects_out_of_universe() {
+    let mut temp = temp_buffer();
+    let mut set = heapless::Vec::<TestRange<u32>, 8>::new();
+    let universe = 0..100;
+
+    // 范围内正常添加
+    set.merge_add_within(TestRange::new(10..20, true), universe.clone(), &mut temp)
+        .unwrap();
+    set.merge_add_within(TestRange::new(90..100, true), universe.clone(), &mut temp)
+        .unwrap();
+    assert_eq!(set.len(), 2);
+
+    // 部分越界与完全越界都被拒绝，集合不变
+    assert_eq!(
+        set.merge_add_within(TestRange::new(95..105, true), universe.clone(), &mut temp),
+        Err(RangeError::OutOfBounds {
+            range: 95..105,
+            universe: 0..100,
+        })
+    );
+    assert!(matches!(
+        set.merge_add_within(TestRange::new(200..300, true), universe.clone(), &mut temp),
+        Err(RangeError::OutOfBounds { .. })
+    ));
+    assert_eq!(
+        set.as_slice(),
+        &[TestRange::new(10..20, true), TestRange::new(90..100, true)]
+    );
+
+    #[cfg(feature = "alloc")]
+    {
+        let mut alloc_set = Vec::new();
+        assert!(matches!(
+            alloc_set.merge_add_within(TestRange::new(5..15, true), 10..20),
+            Err(RangeError::OutOfBounds { .. })
+        ));
+        assert!(alloc_set.is_empty());
+    }
+}
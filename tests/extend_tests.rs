@@ -0,0 +1,510 @@
+#![cfg(any(windows, unix))]
+
+mod common;
+use common::*;
+
+#[test]
+fn extend_counted_reports_all_inserted() {
+    let mut set = heapless::Vec::<TestRange<i32>, 16>::new();
+    let mut temp = temp_buffer();
+
+    let count = set
+        .merge_extend_counted(
+            [
+                TestRange::new(0..5, true),
+                TestRange::new(10..15, true),
+                TestRange::new(20..25, true),
+            ],
+            &mut temp,
+        )
+        .unwrap();
+
+    assert_eq!(count, 3);
+    assert_eq!(set.len(), 3);
+}
+
+#[test]
+fn extend_counted_reports_partial_progress() {
+    let mut set = heapless::Vec::<TestRange<i32>, 2>::new();
+    let mut temp = temp_buffer();
+
+    // 第三个区间会导致容量溢出
+    let result = set.merge_extend_counted(
+        [
+            TestRange::new(0..5, true),
+            TestRange::new(10..15, true),
+            TestRange::new(20..25, true),
+        ],
+        &mut temp,
+    );
+
+    assert_eq!(result, Err((2, RangeError::Capacity)));
+    assert_eq!(set.len(), 2);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn alloc_extend_counted_reports_conflict() {
+    let mut set = std::vec::Vec::<TestRangeWithKind<i32, i32>>::new();
+    set.merge_add(TestRangeWithKind::new(10..20, 1, false))
+        .unwrap();
+
+    // 第二个区间与不可覆盖区间冲突
+    let result = set.merge_extend_counted([
+        TestRangeWithKind::new(0..5, 2, true),
+        TestRangeWithKind::new(15..25, 2, true),
+        TestRangeWithKind::new(30..35, 2, true),
+    ]);
+
+    let (count, err) = result.unwrap_err();
+    assert_eq!(count, 1);
+    assert!(matches!(err, RangeError::Conflict { .. }));
+    assert_eq!(set.len(), 2);
+}
+
+#[test]
+fn extend_atomic_rolls_back_on_conflict() {
+    let mut set = heapless::Vec::<TestRangeWithKind<i32, i32>, 16>::new();
+    let mut temp = temp_buffer();
+    set.merge_add(TestRangeWithKind::new(10..20, 1, false), &mut temp)
+        .unwrap();
+    let before = set.clone();
+
+    // 第一个区间本可以成功，但第二个区间冲突，整体回滚
+    let result = set.merge_extend_atomic(
+        [
+            TestRangeWithKind::new(0..5, 2, true),
+            TestRangeWithKind::new(15..25, 2, true),
+        ],
+        &mut temp,
+    );
+
+    assert!(matches!(result, Err(RangeError::Conflict { .. })));
+    assert_eq!(set, before);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn alloc_extend_atomic_rolls_back_on_conflict() {
+    let mut set = std::vec::Vec::<TestRangeWithKind<i32, i32>>::new();
+    set.merge_add(TestRangeWithKind::new(10..20, 1, false))
+        .unwrap();
+    let before = set.clone();
+
+    let result = set.merge_extend_atomic([
+        TestRangeWithKind::new(0..5, 2, true),
+        TestRangeWithKind::new(15..25, 2, true),
+    ]);
+
+    assert!(matches!(result, Err(RangeError::Conflict { .. })));
+    assert_eq!(set, before);
+
+    // 全部成功时正常提交
+    set.merge_extend_atomic([
+        TestRangeWithKind::new(0..5, 2, true),
+        TestRangeWithKind::new(30..35, 2, true),
+    ])
+    .unwrap();
+    assert_eq!(set.len(), 3);
+}
+
+#[test]
+fn extend_sorted_disjoint_merges_with_tail() {
+    let mut set = heapless::Vec::<TestRangeWithKind<i32, i32>, 16>::new();
+    let mut temp = temp_buffer();
+    set.merge_add(TestRangeWithKind::new(0..10, 1, true), &mut temp)
+        .unwrap();
+
+    set.extend_sorted_disjoint([
+        TestRangeWithKind::new(10..20, 1, true), // 与末尾合并
+        TestRangeWithKind::new(25..30, 1, true),
+        TestRangeWithKind::new(30..35, 2, true), // 不同 kind 不合并
+    ])
+    .unwrap();
+
+    assert_eq!(
+        set.as_slice(),
+        &[
+            TestRangeWithKind::new(0..20, 1, true),
+            TestRangeWithKind::new(25..30, 1, true),
+            TestRangeWithKind::new(30..35, 2, true),
+        ]
+    );
+    assert_eq!(set.validate(), Ok(()));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn alloc_extend_sorted_disjoint_appends() {
+    let mut set = std::vec::Vec::<TestRange<u32>>::new();
+    set.extend_sorted_disjoint((0..100u32).map(|i| TestRange::new(i * 10..i * 10 + 10, true)))
+        .unwrap();
+
+    assert_eq!(set, [TestRange::new(0..1000, true)]);
+}
+
+#[test]
+fn add_many_same_kind_matches_loop() {
+    let ranges = [5..8, 0..3, 3..5, 20..25, 7..12, 22..30, 40..40, 50..55];
+    let template = TestRangeWithKind::new(0..0, 7, true);
+    let mut temp = temp_buffer();
+
+    let mut base = heapless::Vec::<TestRangeWithKind<i32, i32>, 16>::new();
+    base.merge_extend(
+        [
+            TestRangeWithKind::new(10..21, 1, true),
+            TestRangeWithKind::new(45..60, 7, true),
+        ],
+        &mut temp,
+    )
+    .unwrap();
+
+    let mut expected = base.clone();
+    for range in ranges.clone() {
+        expected
+            .merge_add(template.clone_with_range(range), &mut temp)
+            .unwrap();
+    }
+
+    let mut actual = base.clone();
+    actual
+        .add_many_same_kind(ranges.clone(), &template, &mut temp)
+        .unwrap();
+    assert_eq!(actual, expected);
+
+    #[cfg(feature = "alloc")]
+    {
+        let mut actual = std::vec::Vec::from(base.as_slice());
+        actual.add_many_same_kind(ranges, &template).unwrap();
+        assert_eq!(actual.as_slice(), expected.as_slice());
+    }
+}
+
+// 简单的线性同余随机数，保证测试可复现
+fn lcg(state: &mut u64) -> u64 {
+    *state = state
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    *state >> 33
+}
+
+#[test]
+fn add_many_same_kind_matches_loop_randomized() {
+    let mut seed = 0x614;
+    let mut temp = temp_buffer();
+    for _ in 0..2000 {
+        let mut base = heapless::Vec::<TestRangeWithKind<i32, u8>, 64>::new();
+        for _ in 0..6 {
+            let start = (lcg(&mut seed) % 60) as i32;
+            let len = (lcg(&mut seed) % 10) as i32;
+            let kind = (lcg(&mut seed) % 3) as u8;
+            let ow = !lcg(&mut seed).is_multiple_of(3);
+            let _ = base.merge_add(
+                TestRangeWithKind::new(start..start + len, kind, ow),
+                &mut temp,
+            );
+        }
+        let template = TestRangeWithKind::new(
+            0..0,
+            (lcg(&mut seed) % 3) as u8,
+            lcg(&mut seed).is_multiple_of(2),
+        );
+        let ranges: std::vec::Vec<_> = (0..(lcg(&mut seed) % 6))
+            .map(|_| {
+                let start = (lcg(&mut seed) % 60) as i32;
+                start..start + (lcg(&mut seed) % 8) as i32
+            })
+            .collect();
+
+        // 输入先排序并合并相接区间，再逐段插入
+        let mut runs = ranges.clone();
+        runs.retain(|r| r.start < r.end);
+        runs.sort_by_key(|r| r.start);
+        runs.dedup_by(|next, prev| {
+            let touches = next.start <= prev.end;
+            if touches {
+                prev.end = prev.end.max(next.end);
+            }
+            touches
+        });
+
+        let mut expected = base.clone();
+        let expected_result = runs.iter().try_for_each(|range| {
+            expected.merge_add(template.clone_with_range(range.clone()), &mut temp)
+        });
+
+        let mut actual = base.clone();
+        let result = actual.add_many_same_kind(ranges.clone(), &template, &mut temp);
+        assert_eq!(
+            result.is_ok(),
+            expected_result.is_ok(),
+            "{base:?} {ranges:?}"
+        );
+        if result.is_ok() {
+            assert_eq!(actual, expected, "{base:?} {ranges:?}");
+        } else {
+            assert_eq!(actual, base);
+        }
+
+        #[cfg(feature = "alloc")]
+        {
+            let mut actual = std::vec::Vec::from(base.as_slice());
+            let result = actual.add_many_same_kind(ranges.clone(), &template);
+            assert_eq!(
+                result.is_ok(),
+                expected_result.is_ok(),
+                "{base:?} {ranges:?}"
+            );
+            if result.is_ok() {
+                assert_eq!(
+                    actual.as_slice(),
+                    expected.as_slice(),
+                    "{base:?} {ranges:?}"
+                );
+            } else {
+                assert_eq!(actual.as_slice(), base.as_slice());
+            }
+        }
+    }
+}
+
+#[test]
+fn add_ranges_builds_from_bare_ranges() {
+    let mut set = heapless::Vec::<TestRange<u32>, 16>::new();
+    let mut temp = temp_buffer();
+
+    set.add_ranges(
+        &[10..20, 0..5, 18..25, 5..8, 40..50],
+        |range| TestRange::new(range, true),
+        &mut temp,
+    )
+    .unwrap();
+
+    assert_eq!(
+        set.as_slice(),
+        &[
+            TestRange::new(0..8, true),
+            TestRange::new(10..25, true),
+            TestRange::new(40..50, true),
+        ]
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn alloc_add_ranges_builds_from_bare_ranges() {
+    let mut set = std::vec::Vec::<TestRange<u32>>::new();
+    set.add_ranges(&[0..5, 5..10, 20..30], |range| TestRange::new(range, true))
+        .unwrap();
+
+    assert_eq!(
+        set,
+        [TestRange::new(0..10, true), TestRange::new(20..30, true)]
+    );
+}
+
+#[test]
+fn builder_merges_fluently() {
+    let mut temp = temp_buffer();
+
+    let set = RangeSetBuilder::<TestRangeWithKind<i32, i32>, 8>::new()
+        .add(TestRangeWithKind::new(20..30, 1, true))
+        .add(TestRangeWithKind::new(0..10, 1, true))
+        .add(TestRangeWithKind::new(5..15, 1, true))
+        .add(TestRangeWithKind::new(15..20, 2, true))
+        .add(TestRangeWithKind::new(40..40, 3, true))
+        .build(&mut temp)
+        .unwrap();
+    assert_eq!(
+        set.as_slice(),
+        &[
+            TestRangeWithKind::new(0..15, 1, true),
+            TestRangeWithKind::new(15..20, 2, true),
+            TestRangeWithKind::new(20..30, 1, true),
+        ]
+    );
+    assert_eq!(set.validate(), Ok(()));
+}
+
+#[test]
+fn builder_matches_merge_extend_with_overrides() {
+    let mut temp = temp_buffer();
+    let items = [
+        TestRangeWithKind::new(0..100, 1, true),
+        TestRangeWithKind::new(10..20, 1, true),
+        TestRangeWithKind::new(50..60, 2, true), // 与第一个区间重叠且 kind 不同
+    ];
+
+    let mut expected = heapless::Vec::<TestRangeWithKind<i32, i32>, 8>::new();
+    expected.merge_extend(items.clone(), &mut temp).unwrap();
+
+    let built = items
+        .into_iter()
+        .fold(RangeSetBuilder::<_, 8>::new(), |b, item| b.add(item))
+        .build(&mut temp)
+        .unwrap();
+    assert_eq!(built, expected);
+
+    // 超出容量
+    let result = RangeSetBuilder::<TestRange<i32>, 1>::new()
+        .add(TestRange::new(0..1, true))
+        .add(TestRange::new(2..3, true))
+        .build(&mut temp);
+    assert_eq!(result, Err(RangeError::Capacity));
+}
+
+#[test]
+fn add_and_report_returns_merged_extent() {
+    let mut set = heapless::Vec::<TestRangeWithKind<i32, i32>, 16>::new();
+    let mut temp = temp_buffer();
+    set.merge_add(TestRangeWithKind::new(0..20, 1, true), &mut temp)
+        .unwrap();
+    set.merge_add(TestRangeWithKind::new(30..40, 1, true), &mut temp)
+        .unwrap();
+
+    let merged = set
+        .add_and_report(TestRangeWithKind::new(20..30, 1, true), &mut temp)
+        .unwrap();
+    assert_eq!(merged, 0..40);
+    assert_eq!(set.len(), 1);
+
+    // 不与邻居合并时返回新区间本身
+    let merged = set
+        .add_and_report(TestRangeWithKind::new(50..60, 2, true), &mut temp)
+        .unwrap();
+    assert_eq!(merged, 50..60);
+
+    #[cfg(feature = "alloc")]
+    {
+        let mut set = Vec::new();
+        set.merge_add(TestRange::new(0..20, true)).unwrap();
+        set.merge_add(TestRange::new(30..40, true)).unwrap();
+        assert_eq!(set.add_and_report(TestRange::new(20..30, true)), Ok(0..40));
+    }
+}
+
+#[test]
+fn merge_with_resolves_each_overlap() {
+    let mut temp = temp_buffer();
+    let higher = |existing: &TestRangeWithKind<i32, i32>,
+                  incoming: &TestRangeWithKind<i32, i32>| {
+        if incoming.kind > existing.kind {
+            incoming.clone()
+        } else {
+            existing.clone()
+        }
+    };
+
+    let mut set = heapless::Vec::<TestRangeWithKind<i32, i32>, 16>::new();
+    set.merge_extend(
+        [
+            TestRangeWithKind::new(0..10, 1, false),
+            TestRangeWithKind::new(20..30, 3, true),
+        ],
+        &mut temp,
+    )
+    .unwrap();
+    let mut other = heapless::Vec::<TestRangeWithKind<i32, i32>, 16>::new();
+    other
+        .merge_extend(
+            [
+                TestRangeWithKind::new(5..25, 2, true),
+                TestRangeWithKind::new(40..50, 1, true),
+            ],
+            &mut temp,
+        )
+        .unwrap();
+
+    // 不可覆盖的元素也由 resolve 决定；空隙部分直接并入
+    set.merge_with(&other, higher, &mut temp).unwrap();
+    assert_eq!(
+        set.as_slice(),
+        &[
+            TestRangeWithKind::new(0..5, 1, false),
+            TestRangeWithKind::new(5..20, 2, true),
+            TestRangeWithKind::new(20..30, 3, true),
+            TestRangeWithKind::new(40..50, 1, true),
+        ]
+    );
+    assert_eq!(set.validate(), Ok(()));
+
+    #[cfg(feature = "alloc")]
+    {
+        let mut alloc_set = vec![TestRangeWithKind::new(0..10, 1, false)];
+        alloc_set
+            .merge_with(&vec![TestRangeWithKind::new(5..15, 2, true)], higher)
+            .unwrap();
+        assert_eq!(
+            alloc_set,
+            [
+                TestRangeWithKind::new(0..5, 1, false),
+                TestRangeWithKind::new(5..15, 2, true),
+            ]
+        );
+    }
+}
+
+impl PriorityRangeInfo for TestRangeWithKind<i32, i32> {
+    type Priority = i32;
+
+    // 十位表示优先级，个位区分同优先级的不同 kind
+    fn priority(&self) -> i32 {
+        self.kind / 10
+    }
+}
+
+#[test]
+fn merge_add_priority_keeps_higher_priority() {
+    let mut temp = temp_buffer();
+    let mut set = heapless::Vec::<TestRangeWithKind<i32, i32>, 16>::new();
+    set.merge_extend(
+        [
+            TestRangeWithKind::new(10..20, 50, true),
+            TestRangeWithKind::new(30..40, 10, false),
+        ],
+        &mut temp,
+    )
+    .unwrap();
+
+    // 低优先级的新区间被已有的高优先级元素截断，即使后者可覆盖；
+    // 高于已有元素时即使后者不可覆盖也会覆盖
+    set.merge_add_priority(TestRangeWithKind::new(0..35, 30, true), &mut temp)
+        .unwrap();
+    assert_eq!(
+        set.as_slice(),
+        &[
+            TestRangeWithKind::new(0..10, 30, true),
+            TestRangeWithKind::new(10..20, 50, true),
+            TestRangeWithKind::new(20..35, 30, true),
+            TestRangeWithKind::new(35..40, 10, false),
+        ]
+    );
+    assert_eq!(set.validate(), Ok(()));
+
+    // 优先级相同时新区间胜出
+    set.merge_add_priority(TestRangeWithKind::new(25..30, 31, true), &mut temp)
+        .unwrap();
+    assert_eq!(
+        &set[2..5],
+        &[
+            TestRangeWithKind::new(20..25, 30, true),
+            TestRangeWithKind::new(25..30, 31, true),
+            TestRangeWithKind::new(30..35, 30, true),
+        ]
+    );
+
+    #[cfg(feature = "alloc")]
+    {
+        let mut alloc_set = vec![TestRangeWithKind::new(0..10, 40, true)];
+        alloc_set
+            .merge_add_priority(TestRangeWithKind::new(5..15, 20, true))
+            .unwrap();
+        assert_eq!(
+            alloc_set,
+            [
+                TestRangeWithKind::new(0..10, 40, true),
+                TestRangeWithKind::new(10..15, 20, true),
+            ]
+        );
+    }
+}
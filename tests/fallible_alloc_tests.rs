@@ -0,0 +1,152 @@
+#![cfg(feature = "fallible-alloc")]
+#![cfg(any(windows, unix))]
+
+mod common;
+use common::*;
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+// 当前线程的分配次数用完后返回空指针，模拟分配失败
+struct FailingAlloc;
+
+std::thread_local! {
+    static BUDGET: Cell<Option<usize>> = const { Cell::new(None) };
+}
+
+fn take_budget() -> bool {
+    BUDGET.with(|b| match b.get() {
+        Some(0) => false,
+        Some(n) => {
+            b.set(Some(n - 1));
+            true
+        }
+        None => true,
+    })
+}
+
+unsafe impl GlobalAlloc for FailingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if !take_budget() {
+            return core::ptr::null_mut();
+        }
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        if !take_budget() {
+            return core::ptr::null_mut();
+        }
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: FailingAlloc = FailingAlloc;
+
+/// 只允许 `budget` 次分配的情况下执行 `f`
+fn with_budget<R>(budget: usize, f: impl FnOnce() -> R) -> R {
+    BUDGET.with(|b| b.set(Some(budget)));
+    let result = f();
+    BUDGET.with(|b| b.set(None));
+    result
+}
+
+#[test]
+fn try_reserve_reports_allocation_failure() {
+    let mut set = vec![TestRange::new(0..10u32, true)];
+    set.shrink_to_fit();
+
+    let result = with_budget(0, || RangeVecAllocOps::try_reserve(&mut set, 16));
+    assert!(result.is_err());
+    assert_eq!(set.capacity(), 1);
+
+    // merge_add 把分配失败报告为 AllocFailed
+    assert_eq!(
+        with_budget(0, || set.merge_add(TestRange::new(20..30, true))),
+        Err(RangeError::AllocFailed)
+    );
+    assert_eq!(set, [TestRange::new(0..10, true)]);
+
+    // 分配恢复后集合仍可正常使用
+    assert!(RangeVecAllocOps::try_reserve(&mut set, 16).is_ok());
+    assert!(set.capacity() >= 17);
+    set.merge_add(TestRange::new(20..30, true)).unwrap();
+    assert_eq!(set.len(), 2);
+}
+
+#[test]
+fn failed_growth_leaves_set_unchanged() {
+    let mut original: Vec<TestRange<u32>> = (0..4)
+        .map(|i| TestRange::new(i * 10 + 10..i * 10 + 15, true))
+        .collect();
+    original.shrink_to_fit();
+
+    // 新元素位于最前面，插入前的增长失败时集合必须保持不变
+    let mut failures = 0;
+    for budget in 0.. {
+        let mut set = original.clone();
+        set.shrink_to_fit();
+        let result = with_budget(budget, || set.merge_add(TestRange::new(0..5, true)));
+        match result {
+            Err(RangeError::AllocFailed) => {
+                assert_eq!(set, original);
+                failures += 1;
+            }
+            Ok(()) => {
+                assert_eq!(set.len(), 5);
+                break;
+            }
+            Err(e) => panic!("unexpected error: {e:?}"),
+        }
+    }
+    // 只有集合本身的增长需要分配，不使用临时缓冲区
+    assert_eq!(failures, 1);
+}
+
+/// 逐步放宽分配次数执行 `op`：返回 `AllocFailed` 时集合必须保持不变，直到成功为止
+fn assert_atomic_under_oom<R: core::fmt::Debug>(
+    original: &Vec<TestRange<u32>>,
+    op: impl Fn(&mut Vec<TestRange<u32>>) -> Result<R, RangeError<TestRange<u32>>>,
+) -> Vec<TestRange<u32>> {
+    for budget in 0.. {
+        // clone 得到的容量与长度相等，任何增长都需要分配
+        let mut set = original.clone();
+        match with_budget(budget, || op(&mut set)) {
+            Err(RangeError::AllocFailed) => assert_eq!(&set, original),
+            Ok(_) => {
+                assert!(budget > 0, "operation should need to grow the set");
+                return set;
+            }
+            Err(e) => panic!("unexpected error: {e:?}"),
+        }
+    }
+    unreachable!()
+}
+
+#[test]
+fn growing_operations_report_alloc_failed() {
+    let original: Vec<TestRange<u32>> = (0..4)
+        .map(|i| TestRange::new(i * 10 + 10..i * 10 + 15, true))
+        .collect();
+
+    // 在元素中间删除、拆分元素都需要多一个位置
+    let set = assert_atomic_under_oom(&original, |set| set.merge_remove(12..13));
+    assert_eq!(set.len(), 5);
+    let set = assert_atomic_under_oom(&original, |set| set.remove_taking(12..13));
+    assert_eq!(set.len(), 5);
+    let set = assert_atomic_under_oom(&original, |set| set.shift_after(12, 100));
+    assert_eq!(set.len(), 5);
+    let set = assert_atomic_under_oom(&original, |set| set.chunk_elements(2));
+    assert_eq!(set.len(), 12);
+
+    // merge_extend_sorted 的窗口改写同样需要增长
+    let set = assert_atomic_under_oom(&original, |set| {
+        set.merge_extend_sorted([TestRange::new(0..5, true), TestRange::new(60..65, true)])
+    });
+    assert_eq!(set.len(), 6);
+}
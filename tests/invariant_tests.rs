@@ -0,0 +1,65 @@
+#![cfg(any(windows, unix))]
+
+mod common;
+use common::*;
+
+fn kinded(range: core::ops::Range<i32>, kind: i32) -> TestRangeWithKind<i32, i32> {
+    TestRangeWithKind::new(range, kind, true)
+}
+
+#[test]
+fn validate_accepts_merged_set() {
+    let mut set = heapless::Vec::<TestRangeWithKind<i32, i32>, 16>::new();
+    let mut temp = temp_buffer();
+    set.merge_extend(
+        [kinded(0..10, 1), kinded(10..20, 2), kinded(5..15, 1)],
+        &mut temp,
+    )
+    .unwrap();
+    set.merge_remove(2..3, &mut temp).unwrap();
+
+    assert_eq!(set.validate(), Ok(()));
+}
+
+#[test]
+fn validate_reports_each_violation() {
+    // 直接构造破坏不变量的集合
+    let mut set = heapless::Vec::<TestRangeWithKind<i32, i32>, 16>::new();
+
+    set.extend([kinded(0..10, 1), kinded(5..5, 1)]);
+    assert_eq!(set.validate(), Err(InvariantError::EmptyRange { index: 1 }));
+
+    set.clear();
+    set.extend([kinded(10..20, 1), kinded(0..5, 1)]);
+    assert_eq!(
+        set.validate(),
+        Err(InvariantError::Unsorted { left: 0, right: 1 })
+    );
+
+    set.clear();
+    set.extend([kinded(0..10, 1), kinded(5..15, 2)]);
+    assert_eq!(
+        set.validate(),
+        Err(InvariantError::Overlap { left: 0, right: 1 })
+    );
+
+    set.clear();
+    set.extend([kinded(0..10, 1), kinded(10..15, 2), kinded(15..20, 2)]);
+    assert_eq!(
+        set.validate(),
+        Err(InvariantError::Unmerged { left: 1, right: 2 })
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn alloc_validate_reports_overlap() {
+    let mut set = std::vec![kinded(0..10, 1), kinded(5..15, 2)];
+    assert_eq!(
+        set.validate(),
+        Err(InvariantError::Overlap { left: 0, right: 1 })
+    );
+
+    set.remove(1);
+    assert_eq!(set.validate(), Ok(()));
+}
@@ -0,0 +1,42 @@
+#![cfg(any(windows, unix))]
+
+mod common;
+use common::*;
+
+#[test]
+fn overlapping_layers_are_both_reported() {
+    let mut set = LayeredRangeSet::<TestRangeWithKind<i32, i32>, 8>::new();
+    set.add(TestRangeWithKind::new(0..20, 1, false)).unwrap();
+    set.add(TestRangeWithKind::new(10..30, 2, false)).unwrap();
+
+    // 不同 kind 互不覆盖
+    assert_eq!(set.len(), 2);
+    assert_eq!(set.kinds_at(5).collect::<Vec<_>>(), [1]);
+    assert_eq!(set.kinds_at(15).collect::<Vec<_>>(), [1, 2]);
+    assert_eq!(set.kinds_at(25).collect::<Vec<_>>(), [2]);
+    assert_eq!(set.kinds_at(30).count(), 0);
+    assert_eq!(set.overlapping(18..22).count(), 2);
+}
+
+#[test]
+fn same_kind_layers_merge() {
+    let mut set = LayeredRangeSet::<TestRangeWithKind<i32, i32>, 2>::new();
+    set.add(TestRangeWithKind::new(0..10, 1, true)).unwrap();
+    set.add(TestRangeWithKind::new(5..15, 2, true)).unwrap();
+    set.add(TestRangeWithKind::new(10..20, 1, true)).unwrap();
+
+    assert_eq!(
+        set.as_slice(),
+        &[
+            TestRangeWithKind::new(0..20, 1, true),
+            TestRangeWithKind::new(5..15, 2, true),
+        ]
+    );
+
+    // 无法合并的新区间超出容量，集合不变
+    assert_eq!(
+        set.add(TestRangeWithKind::new(40..50, 3, true)),
+        Err(RangeError::Capacity)
+    );
+    assert_eq!(set.len(), 2);
+}
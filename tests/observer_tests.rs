@@ -0,0 +1,104 @@
+#![cfg(any(windows, unix))]
+
+mod common;
+use common::*;
+
+type Kinded = TestRangeWithKind<i32, i32>;
+
+#[derive(Default)]
+struct CountingObserver {
+    merges: usize,
+    splits: usize,
+    conflicts: usize,
+}
+
+impl MergeObserver<Kinded> for CountingObserver {
+    fn on_merge(&mut self, _merged: &Kinded) {
+        self.merges += 1;
+    }
+
+    fn on_split(&mut self, _part: &mut Kinded) {
+        self.splits += 1;
+    }
+
+    fn on_conflict(&mut self, _new: &Kinded, _existing: &Kinded) {
+        self.conflicts += 1;
+    }
+}
+
+#[test]
+fn observer_counts_merge_split_and_conflict() {
+    let mut set = heapless::Vec::<Kinded, 16>::new();
+    let mut temp = temp_buffer();
+    let mut obs = CountingObserver::default();
+
+    // 空集合上添加：没有任何回调
+    set.merge_add_observed(Kinded::new(0..10, 1, true), &mut obs, &mut temp)
+        .unwrap();
+    // 与同 kind 元素相接：合并一次
+    set.merge_add_observed(Kinded::new(10..20, 1, true), &mut obs, &mut temp)
+        .unwrap();
+    assert_eq!((obs.merges, obs.splits, obs.conflicts), (1, 0, 0));
+
+    // 不同 kind 落在中间：原元素被切成两段
+    set.merge_add_observed(Kinded::new(5..15, 2, true), &mut obs, &mut temp)
+        .unwrap();
+    assert_eq!((obs.merges, obs.splits, obs.conflicts), (1, 2, 0));
+
+    // 与不可覆盖的元素冲突
+    set.merge_add_observed(Kinded::new(30..40, 3, false), &mut obs, &mut temp)
+        .unwrap();
+    assert!(
+        set.merge_add_observed(Kinded::new(35..45, 4, true), &mut obs, &mut temp)
+            .is_err()
+    );
+    assert_eq!((obs.merges, obs.splits, obs.conflicts), (1, 2, 1));
+
+    // 删除中间一段：保留下来的左右两段各触发一次分割
+    set.merge_remove_observed(32..34, &mut obs, &mut temp)
+        .unwrap();
+    assert_eq!((obs.merges, obs.splits, obs.conflicts), (1, 4, 1));
+    assert_eq!(set.validate(), Ok(()));
+}
+
+#[test]
+fn noop_observer_matches_plain_merge_add() {
+    let mut observed = heapless::Vec::<Kinded, 16>::new();
+    let mut plain = heapless::Vec::<Kinded, 16>::new();
+    let mut temp = temp_buffer();
+    let input = [
+        Kinded::new(0..10, 1, true),
+        Kinded::new(5..15, 2, true),
+        Kinded::new(15..20, 2, true),
+    ];
+
+    for info in input {
+        observed
+            .merge_add_observed(info.clone(), &mut NoopObserver, &mut temp)
+            .unwrap();
+        plain.merge_add(info, &mut temp).unwrap();
+    }
+    assert_eq!(observed, plain);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn alloc_observer_counts_split() {
+    let mut set = std::vec::Vec::<Kinded>::new();
+    let mut obs = CountingObserver::default();
+
+    set.merge_add_observed(Kinded::new(0..30, 1, true), &mut obs)
+        .unwrap();
+    set.merge_add_observed(Kinded::new(10..20, 2, true), &mut obs)
+        .unwrap();
+    set.merge_remove_observed(0..5, &mut obs).unwrap();
+    assert_eq!((obs.merges, obs.splits, obs.conflicts), (0, 3, 0));
+    assert_eq!(
+        set,
+        [
+            Kinded::new(5..10, 1, true),
+            Kinded::new(10..20, 2, true),
+            Kinded::new(20..30, 1, true),
+        ]
+    );
+}
@@ -59,6 +59,27 @@ fn test_identical_ranges() {
     assert_eq!(set.as_slice()[0].kind(), 2);
 }
 
+#[test]
+fn test_identical_range_kind_flip_flop() {
+    // 相同区间反复切换 kind，每次都只留下一个最新 kind 的元素，不产生空片段
+    let mut set = heapless::Vec::<TestRangeWithKind<i32, i32>, 128>::default();
+    #[cfg(feature = "alloc")]
+    let mut alloc_set = Vec::<TestRangeWithKind<i32, i32>>::new();
+
+    for kind in [1, 2, 1] {
+        let info = TestRangeWithKind::new(0..10, kind, true);
+        set.test_add(info.clone()).unwrap();
+        assert_eq!(set.as_slice(), core::slice::from_ref(&info));
+        assert_eq!(set.validate(), Ok(()));
+
+        #[cfg(feature = "alloc")]
+        {
+            alloc_set.test_add(info.clone()).unwrap();
+            assert_eq!(alloc_set, [info]);
+        }
+    }
+}
+
 #[test]
 fn test_extend_with_errors() {
     // 测试 extend 方法遇到错误时的行为
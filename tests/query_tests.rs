@@ -0,0 +1,601 @@
+#![cfg(any(windows, unix))]
+
+mod common;
+use common::*;
+
+fn kinded(range: core::ops::Range<i32>, kind: i32) -> TestRangeWithKind<i32, i32> {
+    TestRangeWithKind::new(range, kind, true)
+}
+
+fn sample() -> heapless::Vec<TestRangeWithKind<i32, i32>, 16> {
+    let mut set = heapless::Vec::new();
+    let mut temp = temp_buffer();
+    set.merge_extend(
+        [
+            kinded(0..10, 1),
+            kinded(10..20, 2),
+            kinded(20..30, 1),
+            kinded(40..50, 1),
+        ],
+        &mut temp,
+    )
+    .unwrap();
+    set
+}
+
+#[test]
+fn covered_by_kind_requires_single_kind_without_gaps() {
+    // 同 kind 相邻元素通常会被合并，这里直接构造两个首尾相接的元素
+    let mut set = heapless::Vec::<TestRangeWithKind<i32, i32>, 16>::new();
+    set.extend([kinded(0..10, 1), kinded(10..20, 2), kinded(20..30, 2)]);
+
+    // 跨越两个同 kind 元素
+    assert!(set.covered_by_kind(12..28, &2));
+    // 跨越 kind 边界
+    assert!(!set.covered_by_kind(5..15, &1));
+    assert!(!set.covered_by_kind(5..15, &2));
+
+    let set = sample();
+    // 跨越空隙
+    assert!(!set.covered_by_kind(25..45, &1));
+    assert!(set.covered_by_kind(40..50, &1));
+    assert!(!set.covered_by_kind(45..55, &1));
+}
+
+#[test]
+fn segments_alternate_gaps_and_covered() {
+    let mut set = heapless::Vec::<TestRangeWithKind<i32, i32>, 16>::new();
+    let mut temp = temp_buffer();
+    set.merge_extend([kinded(10..20, 1), kinded(30..40, 2)], &mut temp)
+        .unwrap();
+
+    let segments: std::vec::Vec<_> = set.segments(0..50).collect();
+    assert_eq!(
+        segments,
+        [
+            Segment::Gap(0..10),
+            Segment::Covered(&set[0]),
+            Segment::Gap(20..30),
+            Segment::Covered(&set[1]),
+            Segment::Gap(40..50),
+        ]
+    );
+
+    // 与 bounds 部分重叠的元素原样返回，不截断
+    let segments: std::vec::Vec<_> = set.segments(15..35).collect();
+    assert_eq!(
+        segments,
+        [
+            Segment::Covered(&set[0]),
+            Segment::Gap(20..30),
+            Segment::Covered(&set[1]),
+        ]
+    );
+}
+
+fn set_of(
+    ranges: &[(core::ops::Range<i32>, i32)],
+) -> heapless::Vec<TestRangeWithKind<i32, i32>, 16> {
+    let mut set = heapless::Vec::new();
+    let mut temp = temp_buffer();
+    set.merge_extend(
+        ranges
+            .iter()
+            .map(|(range, kind)| kinded(range.clone(), *kind)),
+        &mut temp,
+    )
+    .unwrap();
+    set
+}
+
+#[test]
+fn subset_and_disjoint_ignore_kind() {
+    let outer = set_of(&[(0..20, 1), (20..40, 2), (50..60, 1)]);
+    let nested = set_of(&[(5..35, 3), (52..58, 3)]);
+    let partial = set_of(&[(35..55, 1)]);
+    let apart = set_of(&[(40..50, 1), (60..70, 1)]);
+
+    // 嵌套：跨越 outer 中 kind 不同的两个元素
+    assert!(nested.is_subset_of(&outer));
+    assert!(!outer.is_subset_of(&nested));
+    assert!(!nested.is_disjoint_from(&outer));
+
+    // 部分重叠
+    assert!(!partial.is_subset_of(&outer));
+    assert!(!partial.is_disjoint_from(&outer));
+
+    // 相同集合
+    assert!(outer.is_subset_of(&outer.clone()));
+    assert!(!outer.is_disjoint_from(&outer.clone()));
+
+    // 相邻但不重叠
+    assert!(apart.is_disjoint_from(&outer));
+    assert!(outer.is_disjoint_from(&apart));
+
+    // 空集合是任何集合的子集
+    let empty = set_of(&[]);
+    assert!(empty.is_subset_of(&outer));
+    assert!(empty.is_disjoint_from(&outer));
+}
+
+#[test]
+fn subset_with_kind_compares_aligned_kinds() {
+    let outer = set_of(&[(0..20, 1), (20..40, 2), (50..60, 1)]);
+
+    // 覆盖和 kind 都一致
+    let refined = set_of(&[(5..20, 1), (20..30, 2), (55..60, 1)]);
+    assert!(refined.is_subset_of_with_kind(&outer));
+
+    // 覆盖一致，但 [15,25) 在 outer 中跨越两个 kind
+    let reclassified = set_of(&[(5..25, 1)]);
+    assert!(reclassified.is_subset_of(&outer));
+    assert!(!reclassified.is_subset_of_with_kind(&outer));
+
+    // 覆盖一致，但 kind 不同
+    let renamed = set_of(&[(50..60, 2)]);
+    assert!(renamed.is_subset_of(&outer));
+    assert!(!renamed.is_subset_of_with_kind(&outer));
+}
+
+#[test]
+fn any_overlap_checks_each_query() {
+    let set = sample();
+
+    // 第一个查询落在空隙中，第二个命中
+    assert!(set.any_overlap(&[30..40, 45..46]));
+    // 全部落在空隙或首尾相接
+    assert!(!set.any_overlap(&[30..40, 50..60, -5..0]));
+    // 空查询和空区间
+    assert!(!set.any_overlap(&[]));
+    assert!(!set.any_overlap(&[5..5, 30..40]));
+}
+
+#[test]
+fn coverage_ratio_over_window() {
+    let set = sample();
+
+    // [0,30) 完全覆盖，[40,50) 覆盖，窗口 [20,60) 中覆盖 20
+    assert_eq!(set.coverage_ratio(20..60), 0.5);
+    assert_eq!(set.coverage_ratio(0..30), 1.0);
+    assert_eq!(set.coverage_ratio(30..40), 0.0);
+    // 空窗口
+    assert_eq!(set.coverage_ratio(10..10), 0.0);
+
+    let empty = set_of(&[]);
+    assert_eq!(empty.coverage_ratio(0..100), 0.0);
+}
+
+#[test]
+fn coverage_ratio_for_wide_coordinates() {
+    let mut temp = temp_buffer();
+
+    // u64 坐标没有 Into<f64>，同样可用
+    let mut set = heapless::Vec::<TestRange<u64>, 4>::new();
+    set.merge_add(TestRange::new(0..u64::MAX / 2, true), &mut temp)
+        .unwrap();
+    assert_eq!(set.coverage_ratio(0..u64::MAX), 0.5);
+
+    // 跨越整个 i32 的窗口，end - start 超出 i32
+    let mut set = heapless::Vec::<TestRange<i32>, 4>::new();
+    set.merge_add(TestRange::new(0..i32::MAX, true), &mut temp)
+        .unwrap();
+    let ratio = set.coverage_ratio(i32::MIN..i32::MAX);
+    assert!((ratio - 0.5).abs() < 1e-9);
+}
+
+#[test]
+fn pairs_yields_adjacent_elements() {
+    let set = set_of(&[(0..10, 1), (10..20, 2), (30..40, 1)]);
+
+    let pairs: std::vec::Vec<_> = set.pairs().collect();
+    assert_eq!(pairs, [(&set[0], &set[1]), (&set[1], &set[2])]);
+
+    let single = set_of(&[(0..10, 1)]);
+    assert_eq!(single.pairs().count(), 0);
+}
+
+#[test]
+fn kind_boundaries_cover_adjacent_and_gap_separated() {
+    let set = set_of(&[
+        (0..10, 1),
+        (10..20, 2), // 相接且 kind 不同
+        (30..40, 1), // 隔着空隙且 kind 不同
+        (50..60, 1), // 隔着空隙但 kind 相同
+    ]);
+
+    let boundaries: std::vec::Vec<_> = set.kind_boundaries().collect();
+    assert_eq!(boundaries, [10, 30]);
+}
+
+#[test]
+fn cursor_scans_monotonically() {
+    let set = sample(); // [0,10)1 [10,20)2 [20,30)1 [40,50)1
+    let mut cursor = set.cursor();
+    assert_eq!(cursor.current(), None);
+
+    assert_eq!(cursor.advance_to(0), Some(&set[0]));
+    assert_eq!(cursor.advance_to(9), Some(&set[0]));
+    assert_eq!(cursor.advance_to(10), Some(&set[1]));
+    assert_eq!(cursor.current(), Some(&set[1]));
+    // 空隙
+    assert_eq!(cursor.advance_to(35), None);
+    assert_eq!(cursor.current(), None);
+    assert_eq!(cursor.advance_to(45), Some(&set[3]));
+    // 超出末尾
+    assert_eq!(cursor.advance_to(50), None);
+    assert_eq!(cursor.position(), Some(50));
+
+    // 与 contains_point 一致
+    let mut cursor = set.cursor();
+    for value in -5..60 {
+        assert_eq!(
+            cursor.advance_to(value).is_some(),
+            set.contains_point(value)
+        );
+    }
+}
+
+#[test]
+fn entry_occupied_returns_existing_element() {
+    let mut set = sample();
+    let before = set.clone();
+
+    assert!(matches!(set.entry(12..15), Entry::Occupied(e) if *e == kinded(10..20, 2)));
+    let elem = set
+        .entry(45..60)
+        .or_insert_with(|r| kinded(r, 3))
+        .unwrap()
+        .clone();
+    assert_eq!(elem, kinded(40..50, 1));
+    assert_eq!(set, before);
+}
+
+#[test]
+fn entry_vacant_inserts_and_merges_neighbors() {
+    let mut set = sample();
+
+    // 落在空隙内，与两侧同 kind 元素首尾相接时合并
+    let elem = set
+        .entry(30..40)
+        .or_insert_with(|r| kinded(r, 1))
+        .unwrap()
+        .clone();
+    assert_eq!(elem, kinded(20..50, 1));
+    assert_eq!(
+        set.as_slice(),
+        &[kinded(0..10, 1), kinded(10..20, 2), kinded(20..50, 1)]
+    );
+    assert_eq!(set.validate(), Ok(()));
+
+    // 起点空闲但与后面的覆盖部分重叠
+    let mut set = sample();
+    let before = set.clone();
+    assert_eq!(
+        set.entry(35..45).or_insert_with(|r| kinded(r, 3)),
+        Err(RangeError::Conflict {
+            new: kinded(35..45, 3),
+            existing: kinded(40..50, 1),
+        })
+    );
+    assert_eq!(set, before);
+
+    #[cfg(feature = "alloc")]
+    {
+        // alloc 后端
+        let mut set = Vec::new();
+        set.merge_add(kinded(0..10, 1)).unwrap();
+        match set.entry(10..20) {
+            Entry::Vacant(vacant) => {
+                assert_eq!(vacant.range(), &(10..20));
+                assert_eq!(
+                    vacant.insert(kinded(10..20, 2)).unwrap(),
+                    &kinded(10..20, 2)
+                );
+            }
+            Entry::Occupied(_) => panic!("10 is not covered"),
+        }
+        assert_eq!(set, [kinded(0..10, 1), kinded(10..20, 2)]);
+    }
+}
+
+#[test]
+fn entry_vacant_rejects_info_outside_gap() {
+    let mut set = heapless::Vec::<UnitRange<i32>, 8>::new();
+    let mut temp = temp_buffer();
+    set.merge_add(UnitRange::new(0..3), &mut temp).unwrap();
+    set.merge_add(UnitRange::new(10..12), &mut temp).unwrap();
+    let before = set.clone();
+
+    // 向左越过前一个元素
+    let Entry::Vacant(vacant) = set.entry(5..6) else {
+        panic!("5 is not covered");
+    };
+    assert_eq!(
+        vacant.insert(UnitRange::new(1..6)),
+        Err(RangeError::Conflict {
+            new: UnitRange::new(1..6),
+            existing: UnitRange::new(0..3),
+        })
+    );
+    assert_eq!(set, before);
+
+    // 整体落在后一个元素之后
+    let Entry::Vacant(vacant) = set.entry(5..6) else {
+        panic!("5 is not covered");
+    };
+    assert!(matches!(
+        vacant.insert(UnitRange::new(20..30)),
+        Err(RangeError::Conflict { .. })
+    ));
+    assert_eq!(set, before);
+    assert_eq!(set.validate(), Ok(()));
+}
+
+#[test]
+fn gaps_within_yields_leading_and_trailing_misses() {
+    let mut cache = heapless::Vec::<TestRange<u64>, 8>::new();
+    let mut temp = temp_buffer();
+    cache
+        .merge_add(TestRange::new(100..200, true), &mut temp)
+        .unwrap();
+
+    // 查询区间部分覆盖已缓存元素，只返回查询区间内未覆盖的首尾两段
+    let misses: Vec<_> = cache.uncovered_within(50..250).collect();
+    assert_eq!(misses, [50..100, 200..250]);
+    assert!(cache.gaps_within(50..250).eq(misses.iter().cloned()));
+
+    #[cfg(feature = "alloc")]
+    {
+        let alloc_cache = cache.to_vec();
+        assert!(
+            alloc_cache
+                .uncovered_within(50..250)
+                .eq(misses.iter().cloned())
+        );
+    }
+
+    // 完全命中与完全未命中
+    assert_eq!(cache.gaps_within(120..180).count(), 0);
+    assert!(cache.gaps_within(300..400).eq(core::iter::once(300..400)));
+}
+
+#[test]
+fn ranges_with_kind_matches_elements() {
+    let set = sample();
+
+    assert_eq!(set.ranges_with_kind().count(), set.len());
+    for ((range, kind), elem) in set.ranges_with_kind().zip(set.iter()) {
+        assert_eq!(range, elem.range());
+        assert_eq!(kind, elem.kind());
+    }
+
+    #[cfg(feature = "alloc")]
+    {
+        let alloc_set: Vec<_> = set.iter().cloned().collect();
+        assert!(alloc_set.ranges_with_kind().eq(set.ranges_with_kind()));
+    }
+}
+
+#[test]
+fn point_index_points_at_covering_element() {
+    let set = sample();
+
+    for value in [0, 9, 10, 25, 49] {
+        let index = set.point_index(value).unwrap();
+        assert!(set[index].range().contains(&value));
+    }
+    assert_eq!(set.point_index(15), Some(1));
+    assert_eq!(set.point_index(30), None);
+    assert_eq!(set.point_index(50), None);
+
+    #[cfg(feature = "alloc")]
+    {
+        let alloc_set: Vec<_> = set.iter().cloned().collect();
+        assert_eq!(alloc_set.point_index(45), Some(3));
+    }
+}
+
+#[test]
+fn first_uncovered_reports_where_coverage_breaks() {
+    // sample: [0,10) [10,20) [20,30) [40,50)
+    let set = sample();
+
+    // 完全覆盖（跨越不同 kind 的相接元素）
+    assert_eq!(set.first_uncovered(5..30), None);
+    assert_eq!(set.first_uncovered(5..5), None);
+    // 开头的空隙
+    assert_eq!(set.first_uncovered(-5..10), Some(-5));
+    // 中间的空隙
+    assert_eq!(set.first_uncovered(25..45), Some(30));
+    // 末尾的空隙
+    assert_eq!(set.first_uncovered(42..60), Some(50));
+
+    #[cfg(feature = "alloc")]
+    {
+        let alloc_set: Vec<_> = set.iter().cloned().collect();
+        assert_eq!(alloc_set.first_uncovered(0..45), Some(30));
+    }
+}
+
+#[test]
+fn contains_any_and_all_differ_on_partial_coverage() {
+    // sample: [0,10) [10,20) [20,30) [40,50)
+    let set = sample();
+
+    // 部分覆盖：any 为 true，all 为 false
+    assert!(set.contains_any(25..45));
+    assert!(!set.contains_all(25..45));
+    // 完全覆盖，跨越多个相接元素
+    assert!(set.contains_any(5..25));
+    assert!(set.contains_all(5..25));
+    // 完全落在空隙中
+    assert!(!set.contains_any(30..40));
+    assert!(!set.contains_all(30..40));
+    // 空区间
+    assert!(!set.contains_any(5..5));
+    assert!(set.contains_all(5..5));
+
+    #[cfg(feature = "alloc")]
+    {
+        let alloc_set: Vec<_> = set.iter().cloned().collect();
+        assert!(alloc_set.contains_any(45..60));
+        assert!(!alloc_set.contains_all(45..60));
+    }
+}
+
+#[test]
+fn elements_slice_borrows_overlapping_run() {
+    // sample: [0,10) [10,20) [20,30) [40,50)
+    let set = sample();
+
+    let slice = set.elements_slice(15..45);
+    assert_eq!(slice.len(), 3);
+    assert!(slice[0].range().start < 45 && slice[0].range().end > 15);
+    let last = slice.last().unwrap().range();
+    assert!(last.start < 45 && last.end > 15);
+    assert_eq!(slice, &set[1..4]);
+
+    // 端点只相接不算重叠
+    assert_eq!(set.elements_slice(30..40), &[]);
+    assert_eq!(set.elements_slice(10..20), &set[1..2]);
+    assert!(set.elements_slice(5..5).is_empty());
+
+    #[cfg(feature = "alloc")]
+    {
+        let alloc_set: Vec<_> = set.iter().cloned().collect();
+        assert_eq!(alloc_set.elements_slice(-5..5), &set[..1]);
+    }
+}
+
+#[test]
+fn relate_splits_partially_overlapping_sets() {
+    type Rel = Relation<TestRangeWithKind<i32, i32>>;
+    let only_left = |range, kind| Rel::OnlyLeft { range, kind };
+    let only_right = |range, kind| Rel::OnlyRight { range, kind };
+    let both = |range, left_kind, right_kind| Rel::Both {
+        range,
+        left_kind,
+        right_kind,
+    };
+
+    let left = set_of(&[(0..20, 1), (20..30, 2), (50..60, 1)]);
+    let right = set_of(&[(10..25, 3), (40..55, 4)]);
+
+    let relations: Vec<_> = left.relate(&right).collect();
+    assert_eq!(
+        relations,
+        [
+            only_left(0..10, 1),
+            both(10..20, 1, 3),
+            both(20..25, 2, 3),
+            only_left(25..30, 2),
+            only_right(40..50, 4),
+            both(50..55, 1, 4),
+            only_left(55..60, 1),
+        ]
+    );
+
+    // 片段按顺序排列且互不重叠
+    assert!(
+        relations
+            .windows(2)
+            .all(|w| w[0].range().end <= w[1].range().start)
+    );
+
+    // 交换两侧后左右互换
+    let swapped: Vec<_> = right.relate(&left).collect();
+    assert_eq!(swapped[0], only_right(0..10, 1));
+    assert_eq!(swapped[2], both(20..25, 3, 2));
+
+    #[cfg(feature = "alloc")]
+    {
+        let alloc_left = left.to_vec();
+        let alloc_right = right.to_vec();
+        assert!(alloc_left.relate(&alloc_right).eq(relations));
+    }
+
+    // 与空集合比较时只剩一侧
+    let empty = set_of(&[]);
+    assert_eq!(empty.relate(&right).count(), right.len());
+    assert_eq!(empty.relate(&empty).next(), None);
+}
+
+// 记录原始子区间的元素：合并时并入对方的子区间，切分时裁剪到新区间内
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct TracedRange {
+    range: core::ops::Range<u32>,
+    originals: heapless::Vec<core::ops::Range<u32>, 8>,
+}
+
+impl TracedRange {
+    fn new(range: core::ops::Range<u32>) -> Self {
+        Self {
+            originals: heapless::Vec::from_slice(core::slice::from_ref(&range)).unwrap(),
+            range,
+        }
+    }
+}
+
+impl RangeInfo for TracedRange {
+    type Kind = ();
+    type Type = u32;
+
+    fn range(&self) -> core::ops::Range<u32> {
+        self.range.clone()
+    }
+
+    fn kind(&self) {}
+
+    fn overwritable(&self) -> bool {
+        true
+    }
+
+    fn clone_with_range(&self, range: core::ops::Range<u32>) -> Self {
+        let originals = self
+            .originals
+            .iter()
+            .map(|o| o.start.max(range.start)..o.end.min(range.end))
+            .filter(|o| o.start < o.end)
+            .collect();
+        Self { range, originals }
+    }
+
+    fn merge_meta(&mut self, other: &Self) {
+        self.originals.extend(other.originals.iter().cloned());
+        self.originals.sort_unstable_by_key(|o| o.start);
+    }
+}
+
+impl ProvenanceRangeInfo for TracedRange {
+    fn originals(&self) -> impl Iterator<Item = core::ops::Range<u32>> {
+        self.originals.iter().cloned()
+    }
+}
+
+#[test]
+fn merged_and_originals_iter_for_merged_element() {
+    let mut set = heapless::Vec::<TracedRange, 8>::new();
+    let mut temp = temp_buffer();
+    set.merge_add(TracedRange::new(0..5), &mut temp).unwrap();
+    set.merge_add(TracedRange::new(5..10), &mut temp).unwrap();
+    set.merge_add(TracedRange::new(20..25), &mut temp).unwrap();
+
+    // 两个原始区间合并成一个元素
+    assert!(set.merged_iter().eq([0..10, 20..25]));
+    assert!(set.originals_iter().eq([0..5, 5..10, 20..25]));
+
+    #[cfg(feature = "alloc")]
+    {
+        let mut alloc_set = std::vec::Vec::new();
+        alloc_set.merge_add(TracedRange::new(5..10)).unwrap();
+        alloc_set.merge_add(TracedRange::new(0..5)).unwrap();
+        alloc_set.merge_add(TracedRange::new(20..25)).unwrap();
+        assert!(alloc_set.merged_iter().eq(set.merged_iter()));
+        assert!(alloc_set.originals_iter().eq(set.originals_iter()));
+    }
+
+    // 删除中间部分后，原始子区间随元素一起被裁剪
+    set.merge_remove(3..7, &mut temp).unwrap();
+    assert!(set.merged_iter().eq([0..3, 7..10, 20..25]));
+    assert!(set.originals_iter().eq([0..3, 7..10, 20..25]));
+}
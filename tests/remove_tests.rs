@@ -0,0 +1,609 @@
+#![cfg(any(windows, unix))]
+
+mod common;
+use common::*;
+
+fn kinded(range: core::ops::Range<i32>, kind: i32) -> TestRangeWithKind<i32, i32> {
+    TestRangeWithKind::new(range, kind, true)
+}
+
+#[test]
+fn remove_by_kind_keeps_neighbors_with_gap() {
+    let mut set = heapless::Vec::<TestRangeWithKind<i32, i32>, 16>::new();
+    let mut temp = temp_buffer();
+    set.merge_extend(
+        [kinded(0..10, 1), kinded(10..20, 2), kinded(20..30, 1)],
+        &mut temp,
+    )
+    .unwrap();
+
+    set.remove_by_kind(&2);
+
+    // 两侧的同 kind 区间保留，中间留下空隙
+    assert_eq!(set.as_slice(), &[kinded(0..10, 1), kinded(20..30, 1)]);
+    assert!(!set.contains_point(15));
+    assert_eq!(set.validate(), Ok(()));
+}
+
+#[test]
+fn remove_if_uses_predicate() {
+    let mut set = heapless::Vec::<TestRangeWithKind<i32, i32>, 16>::new();
+    let mut temp = temp_buffer();
+    set.merge_extend(
+        [kinded(0..5, 1), kinded(10..30, 2), kinded(40..42, 3)],
+        &mut temp,
+    )
+    .unwrap();
+
+    let mut removed = 0;
+    set.remove_if(|elem| {
+        let hit = elem.range().end - elem.range().start < 10;
+        if hit {
+            removed += 1;
+        }
+        hit
+    });
+
+    assert_eq!(removed, 2);
+    assert_eq!(set.as_slice(), &[kinded(10..30, 2)]);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn alloc_remove_by_kind_keeps_neighbors_with_gap() {
+    let mut set = std::vec::Vec::<TestRangeWithKind<i32, i32>>::new();
+    set.merge_extend([kinded(0..10, 1), kinded(10..20, 2), kinded(20..30, 1)])
+        .unwrap();
+
+    set.remove_by_kind(&2);
+    assert_eq!(set, [kinded(0..10, 1), kinded(20..30, 1)]);
+
+    set.remove_if(|elem| elem.range().start == 0);
+    assert_eq!(set, [kinded(20..30, 1)]);
+}
+
+#[test]
+fn retain_within_keeps_boundary_elements_untrimmed() {
+    let mut set = heapless::Vec::<TestRangeWithKind<i32, i32>, 16>::new();
+    let mut temp = temp_buffer();
+    set.merge_extend(
+        [
+            kinded(0..10, 1),
+            kinded(15..25, 2),
+            kinded(30..40, 1),
+            kinded(45..55, 2),
+            kinded(60..70, 1),
+        ],
+        &mut temp,
+    )
+    .unwrap();
+
+    set.retain_within(20..50);
+
+    // 完全在外部的元素被删除，边界上的元素保持原样
+    assert_eq!(
+        set.as_slice(),
+        &[kinded(15..25, 2), kinded(30..40, 1), kinded(45..55, 2)]
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn alloc_retain_within_drops_touching_elements() {
+    let mut set = std::vec::Vec::<TestRangeWithKind<i32, i32>>::new();
+    set.merge_extend([kinded(0..10, 1), kinded(10..20, 2), kinded(20..30, 1)])
+        .unwrap();
+
+    // 只与 bounds 首尾相接的元素没有交集
+    set.retain_within(10..20);
+    assert_eq!(set, [kinded(10..20, 2)]);
+}
+
+#[test]
+fn chunk_elements_then_coalesce_round_trips() {
+    let mut set = heapless::Vec::<TestRangeWithKind<i32, i32>, 16>::new();
+    let mut temp = temp_buffer();
+    set.merge_extend([kinded(0..10, 1), kinded(10..13, 2)], &mut temp)
+        .unwrap();
+    let before = set.clone();
+
+    set.chunk_elements(4, &mut temp).unwrap();
+    assert_eq!(
+        set.as_slice(),
+        &[
+            kinded(0..4, 1),
+            kinded(4..8, 1),
+            kinded(8..10, 1),
+            kinded(10..13, 2),
+        ]
+    );
+    assert_eq!(
+        set.validate(),
+        Err(InvariantError::Unmerged { left: 0, right: 1 })
+    );
+
+    set.coalesce();
+    assert_eq!(set, before);
+
+    // 容量不足时不修改集合
+    let mut small = heapless::Vec::<TestRangeWithKind<i32, i32>, 2>::new();
+    small.merge_add(kinded(0..10, 1), &mut temp).unwrap();
+    assert_eq!(
+        small.chunk_elements(3, &mut temp),
+        Err(RangeError::Capacity)
+    );
+    assert_eq!(small.as_slice(), &[kinded(0..10, 1)]);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn alloc_chunk_elements_splits_large_element() {
+    let mut set = std::vec::Vec::<TestRange<u64>>::new();
+    set.merge_add(TestRange::new(0..1_000, true)).unwrap();
+
+    set.chunk_elements(64).unwrap();
+    assert_eq!(set.len(), 16);
+    assert!(set.iter().all(|e| e.range.end - e.range.start <= 64));
+    assert_eq!(set.last().unwrap().range, 960..1_000);
+
+    set.coalesce();
+    assert_eq!(set, [TestRange::new(0..1_000, true)]);
+}
+
+#[test]
+fn add_and_remove_after_chunk_elements() {
+    let mut set = heapless::Vec::<TestRangeWithKind<i32, i32>, 16>::new();
+    let mut temp = temp_buffer();
+    set.merge_add(kinded(0..12, 1), &mut temp).unwrap();
+    set.chunk_elements(4, &mut temp).unwrap();
+
+    // 切分留下的未合并元素不影响之后与其无关的添加和删除
+    set.merge_add(kinded(20..25, 2), &mut temp).unwrap();
+    set.merge_remove(21..22, &mut temp).unwrap();
+    set.merge_add(kinded(12..14, 3), &mut temp).unwrap();
+    set.merge_remove_many([9..10, 23..24], &mut temp).unwrap();
+    assert_eq!(
+        set.as_slice(),
+        &[
+            kinded(0..4, 1),
+            kinded(4..8, 1),
+            kinded(8..9, 1),
+            kinded(10..12, 1),
+            kinded(12..14, 3),
+            kinded(20..21, 2),
+            kinded(22..23, 2),
+            kinded(24..25, 2),
+        ]
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn alloc_add_and_remove_after_chunk_elements() {
+    let mut set = std::vec::Vec::<TestRangeWithKind<i32, i32>>::new();
+    set.merge_add(kinded(0..12, 1)).unwrap();
+    set.chunk_elements(4).unwrap();
+
+    set.merge_add(kinded(20..25, 2)).unwrap();
+    set.merge_remove(21..22).unwrap();
+    set.merge_add(kinded(12..14, 3)).unwrap();
+    set.merge_remove_many([9..10, 23..24]).unwrap();
+    set.merge_extend_sorted([kinded(30..31, 2)]).unwrap();
+    assert_eq!(set.len(), 9);
+    assert_eq!(
+        set.validate(),
+        Err(InvariantError::Unmerged { left: 0, right: 1 })
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn remove_taking_returns_fragments_with_kind() {
+    let mut set = std::vec::Vec::<TestRangeWithKind<i32, i32>>::new();
+    set.merge_add(TestRangeWithKind::new(0..30, 1, false))
+        .unwrap();
+    set.merge_add(kinded(30..40, 2)).unwrap();
+
+    // 删除元素中间的一段：返回的片段带有原 kind 和 overwritable
+    let taken = set.remove_taking(10..20).unwrap();
+    assert_eq!(taken, [TestRangeWithKind::new(10..20, 1, false)]);
+    assert_eq!(
+        set,
+        [
+            TestRangeWithKind::new(0..10, 1, false),
+            TestRangeWithKind::new(20..30, 1, false),
+            kinded(30..40, 2),
+        ]
+    );
+
+    // 跨多个元素删除，片段按顺序返回，重新添加即可撤销
+    let taken = set.remove_taking(25..35).unwrap();
+    assert_eq!(
+        taken,
+        [TestRangeWithKind::new(25..30, 1, false), kinded(30..35, 2)]
+    );
+    set.merge_extend(taken).unwrap();
+    assert_eq!(
+        set,
+        [
+            TestRangeWithKind::new(0..10, 1, false),
+            TestRangeWithKind::new(20..30, 1, false),
+            kinded(30..40, 2),
+        ]
+    );
+
+    assert!(set.remove_taking(12..18).unwrap().is_empty());
+}
+
+#[test]
+fn coalesce_by_merges_compatible_kinds() {
+    let mut set = heapless::Vec::<TestRangeWithKind<i32, i32>, 16>::new();
+    let mut temp = temp_buffer();
+    set.merge_extend(
+        [
+            kinded(0..10, 1),
+            kinded(10..20, 3),
+            kinded(20..30, 4),
+            kinded(35..40, 5),
+        ],
+        &mut temp,
+    )
+    .unwrap();
+
+    // 奇偶性相同的 kind 视为等价，合并结果沿用左侧 kind；不相接的元素保持独立
+    set.coalesce_by(|a, b| a % 2 == b % 2);
+    assert_eq!(
+        set.as_slice(),
+        &[kinded(0..20, 1), kinded(20..30, 4), kinded(35..40, 5)]
+    );
+    assert_eq!(set.validate(), Ok(()));
+}
+
+#[test]
+fn reclassify_middle_of_element() {
+    let mut set = heapless::Vec::<TestRangeWithKind<i32, i32>, 16>::new();
+    let mut temp = temp_buffer();
+    set.merge_add(TestRangeWithKind::new(0..30, 1, false), &mut temp)
+        .unwrap();
+
+    // 不可覆盖的元素同样可以被重新分类
+    set.reclassify(10..20, &kinded(0..0, 2), &mut temp).unwrap();
+    assert_eq!(
+        set.as_slice(),
+        &[
+            TestRangeWithKind::new(0..10, 1, false),
+            kinded(10..20, 2),
+            TestRangeWithKind::new(20..30, 1, false),
+        ]
+    );
+
+    // 与新 kind 的相邻元素合并
+    set.reclassify(20..30, &kinded(0..0, 2), &mut temp).unwrap();
+    assert_eq!(
+        set.as_slice(),
+        &[TestRangeWithKind::new(0..10, 1, false), kinded(10..30, 2)]
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn alloc_reclassify_middle_of_element() {
+    let mut set = std::vec::Vec::<TestRangeWithKind<i32, i32>>::new();
+    set.merge_add(kinded(0..30, 1)).unwrap();
+
+    set.reclassify(10..20, &kinded(0..0, 2)).unwrap();
+    assert_eq!(
+        set,
+        [kinded(0..10, 1), kinded(10..20, 2), kinded(20..30, 1)]
+    );
+}
+
+#[test]
+fn tombstone_keeps_coverage_and_gaps() {
+    const DELETED: i32 = -1;
+    let mut set = heapless::Vec::<TestRangeWithKind<i32, i32>, 16>::new();
+    let mut temp = temp_buffer();
+    set.merge_extend(
+        [
+            kinded(0..10, 1),
+            TestRangeWithKind::new(10..20, 2, false),
+            kinded(30..40, 3),
+        ],
+        &mut temp,
+    )
+    .unwrap();
+    let before = set.total_len();
+
+    // [20,30) 是空隙，保持为空隙；不可覆盖的元素同样被标记
+    set.tombstone(5..35, DELETED, &kinded(0..0, DELETED), &mut temp)
+        .unwrap();
+    assert_eq!(
+        set.as_slice(),
+        &[
+            kinded(0..5, 1),
+            kinded(5..20, DELETED),
+            kinded(30..35, DELETED),
+            kinded(35..40, 3),
+        ]
+    );
+    assert_eq!(set.total_len(), before);
+    assert!(!set.contains_point(25));
+
+    // 模板的 kind 与 sentinel 不一致时拒绝，集合不变
+    let snapshot = set.clone();
+    assert_eq!(
+        set.tombstone(0..40, DELETED, &kinded(0..0, 7), &mut temp),
+        Err(RangeError::KindMismatch {
+            expected: DELETED,
+            found: 7,
+        })
+    );
+    assert_eq!(set, snapshot);
+
+    #[cfg(feature = "alloc")]
+    {
+        let mut alloc_set = vec![kinded(0..10, 1), kinded(20..30, 2)];
+        assert!(matches!(
+            alloc_set.tombstone(0..100, DELETED, &kinded(0..0, 1)),
+            Err(RangeError::KindMismatch { .. })
+        ));
+        alloc_set
+            .tombstone(0..100, DELETED, &kinded(0..0, DELETED))
+            .unwrap();
+        assert_eq!(alloc_set, [kinded(0..10, DELETED), kinded(20..30, DELETED)]);
+    }
+}
+
+#[test]
+fn try_remove_refuses_non_overwritable() {
+    let mut set = heapless::Vec::<TestRangeWithKind<i32, i32>, 16>::new();
+    let mut temp = temp_buffer();
+    let protected = TestRangeWithKind::new(20..30, 2, false);
+    set.merge_extend([kinded(0..10, 1), protected.clone()], &mut temp)
+        .unwrap();
+    let before = set.clone();
+
+    let err = set.try_merge_remove(5..25, &mut temp).unwrap_err();
+    assert_eq!(
+        err,
+        RangeError::Conflict {
+            new: TestRangeWithKind::new(5..25, 2, false),
+            existing: protected,
+        }
+    );
+    assert_eq!(set, before);
+
+    // 只覆盖可覆盖元素时正常删除
+    set.try_merge_remove(5..15, &mut temp).unwrap();
+    assert_eq!(
+        set.as_slice(),
+        &[kinded(0..5, 1), TestRangeWithKind::new(20..30, 2, false)]
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn alloc_try_remove_refuses_non_overwritable() {
+    let mut set = Vec::<TestRange<i32>>::new();
+    set.merge_add(TestRange::new(0..10, false)).unwrap();
+
+    assert!(matches!(
+        set.try_merge_remove(8..12),
+        Err(RangeError::Conflict { .. })
+    ));
+    assert_eq!(set, [TestRange::new(0..10, false)]);
+
+    // 不检查保护的删除仍然可用
+    set.merge_remove(8..12).unwrap();
+    assert_eq!(set, [TestRange::new(0..8, false)]);
+}
+
+#[test]
+fn refilling_removed_gap_coalesces_both_halves() {
+    let mut set = heapless::Vec::<TestRangeWithKind<i32, i32>, 16>::new();
+    let mut temp = temp_buffer();
+    set.merge_add(kinded(0..30, 1), &mut temp).unwrap();
+    set.merge_add(kinded(40..50, 2), &mut temp).unwrap();
+
+    set.merge_remove(10..20, &mut temp).unwrap();
+    assert_eq!(
+        set.as_slice(),
+        &[kinded(0..10, 1), kinded(20..30, 1), kinded(40..50, 2)]
+    );
+
+    // 恰好填满空隙：两侧的同 kind 片段与新区间合并为一个元素
+    set.merge_add(kinded(10..20, 1), &mut temp).unwrap();
+    assert_eq!(set.as_slice(), &[kinded(0..30, 1), kinded(40..50, 2)]);
+
+    // 跨越两侧片段内部的新区间同样合并
+    set.merge_remove(10..20, &mut temp).unwrap();
+    set.merge_add(kinded(5..25, 1), &mut temp).unwrap();
+    assert_eq!(set.as_slice(), &[kinded(0..30, 1), kinded(40..50, 2)]);
+    assert_eq!(set.validate(), Ok(()));
+
+    #[cfg(feature = "alloc")]
+    {
+        let mut set = Vec::new();
+        set.merge_add(kinded(0..30, 1)).unwrap();
+        set.merge_remove(10..20).unwrap();
+        set.merge_add(kinded(10..20, 1)).unwrap();
+        assert_eq!(set, [kinded(0..30, 1)]);
+    }
+}
+
+#[test]
+fn remove_with_stats_reports_split_and_removed() {
+    let mut set = heapless::Vec::<TestRangeWithKind<i32, i32>, 16>::new();
+    let mut temp = temp_buffer();
+    set.merge_extend(
+        [
+            kinded(0..10, 1),
+            kinded(10..20, 2),
+            kinded(20..30, 1),
+            kinded(40..50, 2),
+        ],
+        &mut temp,
+    )
+    .unwrap();
+
+    // 从中间分割一个元素
+    let stats = set.remove_with_stats(42..45, &mut temp).unwrap();
+    assert_eq!(
+        stats,
+        RemoveStats {
+            removed: 0,
+            split: 1,
+            remaining_delta: 1,
+        }
+    );
+    assert_eq!(set.len(), 5);
+
+    // 整体删除两个元素，另截掉一个元素的尾部
+    let stats = set.remove_with_stats(5..35, &mut temp).unwrap();
+    assert_eq!(
+        stats,
+        RemoveStats {
+            removed: 2,
+            split: 0,
+            remaining_delta: -2,
+        }
+    );
+    assert_eq!(
+        set.as_slice(),
+        &[kinded(0..5, 1), kinded(40..42, 2), kinded(45..50, 2)]
+    );
+
+    #[cfg(feature = "alloc")]
+    {
+        let mut alloc_set = vec![kinded(0..10, 1), kinded(10..20, 2)];
+        let stats = alloc_set.remove_with_stats(0..20).unwrap();
+        assert_eq!(stats.removed, 2);
+        assert_eq!(stats.remaining_delta, -2);
+        assert!(alloc_set.is_empty());
+        assert_eq!(
+            alloc_set.remove_with_stats(0..20),
+            Ok(RemoveStats::default())
+        );
+    }
+}
+
+#[test]
+fn truncate_keeps_lowest_elements() {
+    let mut set = heapless::Vec::<TestRangeWithKind<i32, i32>, 8>::new();
+    let mut temp = temp_buffer();
+    set.merge_extend((0..5).map(|i| kinded(i * 10..i * 10 + 5, i)), &mut temp)
+        .unwrap();
+    assert_eq!(set.len(), 5);
+
+    set.truncate(3);
+    assert_eq!(
+        set.as_slice(),
+        &[kinded(0..5, 0), kinded(10..15, 1), kinded(20..25, 2)]
+    );
+    assert!(!set.contains_point(30) && !set.contains_point(40));
+    assert_eq!(set.validate(), Ok(()));
+
+    let mut alloc_set: Vec<_> = set.iter().cloned().collect();
+    alloc_set.truncate(1);
+    assert_eq!(alloc_set, [kinded(0..5, 0)]);
+}
+
+#[test]
+fn pop_first_drains_in_ascending_order() {
+    let mut set = heapless::Vec::<TestRangeWithKind<i32, i32>, 8>::new();
+    let mut temp = temp_buffer();
+    set.merge_extend(
+        [
+            kinded(30..35, 1),
+            kinded(0..5, 2),
+            kinded(20..25, 3),
+            kinded(10..15, 4),
+        ],
+        &mut temp,
+    )
+    .unwrap();
+
+    assert_eq!(set.pop_last(), Some(kinded(30..35, 1)));
+    let mut starts = Vec::new();
+    while let Some(elem) = set.pop_first() {
+        starts.push(elem.range.start);
+    }
+    assert_eq!(starts, [0, 10, 20]);
+    assert_eq!(set.pop_first(), None);
+    assert_eq!(set.pop_last(), None);
+
+    #[cfg(feature = "alloc")]
+    {
+        let mut alloc_set = vec![kinded(0..5, 1), kinded(10..15, 2)];
+        assert_eq!(alloc_set.pop_first(), Some(kinded(0..5, 1)));
+        assert_eq!(alloc_set.pop_last(), Some(kinded(10..15, 2)));
+        assert!(alloc_set.is_empty());
+    }
+}
+
+#[test]
+fn drop_smaller_than_removes_tiny_fragments() {
+    let mut set = heapless::Vec::<TestRangeWithKind<i32, i32>, 8>::new();
+    let mut temp = temp_buffer();
+    set.merge_extend(
+        [kinded(0..10, 1), kinded(10..12, 2), kinded(20..25, 3)],
+        &mut temp,
+    )
+    .unwrap();
+
+    // 长度恰好等于 min_len 的元素保留
+    set.drop_smaller_than(5);
+    assert_eq!(set.as_slice(), &[kinded(0..10, 1), kinded(20..25, 3)]);
+
+    #[cfg(feature = "alloc")]
+    {
+        // 长度超出坐标类型范围的元素不会被误删
+        let mut alloc_set = vec![
+            TestRangeWithKind::new(i32::MIN..1, 1, true),
+            TestRangeWithKind::new(5..7, 2, true),
+        ];
+        alloc_set.drop_smaller_than(5);
+        assert_eq!(alloc_set.len(), 1);
+    }
+}
+
+#[test]
+fn merge_remove_many_matches_remove_loop() {
+    let elements = [
+        kinded(0..10, 1),
+        kinded(10..20, 2),
+        kinded(25..40, 3),
+        kinded(50..60, 4),
+    ];
+    // 乱序、重叠、空区间以及跨越多个元素的区间
+    let cuts = [35..52, 2..4, 3..6, 18..27, 7..7, 58..100, 8..12, -5..-1];
+
+    let mut expected = heapless::Vec::<TestRangeWithKind<i32, i32>, 16>::new();
+    let mut temp = temp_buffer();
+    expected.merge_extend(elements.clone(), &mut temp).unwrap();
+    let mut set = expected.clone();
+    for cut in cuts.clone() {
+        expected.merge_remove(cut, &mut temp).unwrap();
+    }
+
+    set.merge_remove_many(cuts.clone(), &mut temp).unwrap();
+    assert_eq!(set, expected);
+    assert!(set.validate().is_ok());
+
+    // 区间数超过容量 N 时分批处理，结果不变
+    let mut small = heapless::Vec::<TestRangeWithKind<i32, i32>, 6>::new();
+    small.merge_extend(elements.clone(), &mut temp).unwrap();
+    let mut small_expected = small.clone();
+    for cut in cuts.clone() {
+        small_expected.merge_remove(cut, &mut temp).unwrap();
+    }
+    small.merge_remove_many(cuts.clone(), &mut temp).unwrap();
+    assert_eq!(small, small_expected);
+
+    #[cfg(feature = "alloc")]
+    {
+        let mut alloc_set = elements.to_vec();
+        alloc_set.merge_remove_many(cuts).unwrap();
+        assert_eq!(alloc_set.as_slice(), expected.as_slice());
+    }
+}
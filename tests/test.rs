@@ -26,6 +26,279 @@ fn add_merges_overlaps_and_adjacency() {
     assert_eq!(set.as_slice(), &expected);
 }
 
+#[test]
+fn unit_range_merges_overlaps_and_adjacency() {
+    let mut set: RangeSet<UnitRange<i32>> = RangeSet::new();
+    let mut temp = temp_buffer();
+    set.merge_add(UnitRange::new(r(10, 20)), &mut temp).unwrap();
+    set.merge_add(UnitRange::new(r(30, 40)), &mut temp).unwrap();
+    set.merge_add(UnitRange::new(r(15, 35)), &mut temp).unwrap();
+    assert_eq!(set.as_slice(), &[UnitRange::new(r(10, 40))]);
+
+    // 相邻也会合并（[10,20) + [20,25) => [10,25)）
+    set.clear();
+    set.merge_add(UnitRange::new(r(10, 20)), &mut temp).unwrap();
+    set.merge_add(UnitRange::new(r(20, 25)), &mut temp).unwrap();
+    assert_eq!(set.as_slice(), &[UnitRange::new(r(10, 25))]);
+
+    // 同 kind 之间不会冲突，protected 只影响是否可被不同 kind 覆盖
+    set.merge_add(UnitRange::protected(r(0, 12)), &mut temp)
+        .unwrap();
+    assert_eq!(set.as_slice(), &[UnitRange::protected(r(0, 25))]);
+}
+
+#[test]
+fn unit_range_default_matches_new() {
+    let default = UnitRange::<i32>::default();
+    assert_eq!(default, UnitRange::new(r(0, 0)));
+    assert!(default.overwritable());
+}
+
+#[test]
+fn tuple_elements_merge_same_kind_neighbors() {
+    let mut set: RangeSet<(core::ops::Range<i32>, &str)> = RangeSet::new();
+    let mut temp = temp_buffer();
+    set.merge_add((r(0, 10), "code"), &mut temp).unwrap();
+    set.merge_add((r(10, 20), "code"), &mut temp).unwrap();
+    set.merge_add((r(20, 30), "data"), &mut temp).unwrap();
+    assert_eq!(set.as_slice(), &[(r(0, 20), "code"), (r(20, 30), "data")]);
+
+    // 元组元素总是可被覆盖
+    set.merge_add((r(15, 25), "stack"), &mut temp).unwrap();
+    assert_eq!(
+        set.as_slice(),
+        &[
+            (r(0, 15), "code"),
+            (r(15, 25), "stack"),
+            (r(25, 30), "data")
+        ]
+    );
+}
+
+impl LockableRangeInfo for TestRangeWithKind<i32, i32> {
+    fn set_overwritable(&mut self, overwritable: bool) {
+        self.overwritable = overwritable;
+    }
+}
+
+#[test]
+fn set_overwritable_splits_partial_elements() {
+    let mut set = heapless::Vec::<TestRangeWithKind<i32, i32>, 8>::new();
+    let mut temp = temp_buffer();
+    set.merge_extend(
+        [
+            TestRangeWithKind::new(r(0, 30), 1, true),
+            TestRangeWithKind::new(r(30, 40), 2, true),
+        ],
+        &mut temp,
+    )
+    .unwrap();
+
+    // 先预留，再把中间一段改为单独的 kind 并锁定：得到三个元素，中间不可覆盖
+    set.reclassify(
+        r(10, 20),
+        &TestRangeWithKind::new(r(0, 0), 3, true),
+        &mut temp,
+    )
+    .unwrap();
+    set.set_overwritable(r(10, 20), false).unwrap();
+    assert_eq!(
+        set.as_slice(),
+        &[
+            TestRangeWithKind::new(r(0, 10), 1, true),
+            TestRangeWithKind::new(r(10, 20), 3, false),
+            TestRangeWithKind::new(r(20, 30), 1, true),
+            TestRangeWithKind::new(r(30, 40), 2, true),
+        ]
+    );
+    assert!(
+        set.merge_add(TestRangeWithKind::new(r(15, 25), 4, true), &mut temp)
+            .is_err()
+    );
+
+    // 只锁定元素的一部分：拆成 overwritable 不同、互不合并的片段
+    set.set_overwritable(r(25, 35), false).unwrap();
+    assert_eq!(
+        set.as_slice(),
+        &[
+            TestRangeWithKind::new(r(0, 10), 1, true),
+            TestRangeWithKind::new(r(10, 20), 3, false),
+            TestRangeWithKind::new(r(20, 25), 1, true),
+            TestRangeWithKind::new(r(25, 30), 1, false),
+            TestRangeWithKind::new(r(30, 35), 2, false),
+            TestRangeWithKind::new(r(35, 40), 2, true),
+        ]
+    );
+    assert_eq!(set.validate(), Ok(()));
+    assert!(
+        set.merge_add(TestRangeWithKind::new(r(26, 27), 4, true), &mut temp)
+            .is_err()
+    );
+
+    // 锁定元素中间一段得到三个片段，容量不足时返回 Capacity 且不修改集合
+    let before = set.clone();
+    let mut small = heapless::Vec::<TestRangeWithKind<i32, i32>, 7>::new();
+    small.extend(before.iter().cloned());
+    assert_eq!(
+        small.set_overwritable(r(2, 4), false),
+        Err(RangeError::Capacity)
+    );
+    assert_eq!(small.as_slice(), before.as_slice());
+
+    // 解锁后与相邻的同 kind 片段重新合并
+    set.set_overwritable(r(25, 35), true).unwrap();
+    assert_eq!(set.len(), 4);
+    assert_eq!(set.validate(), Ok(()));
+
+    // 已经是目标值的部分重叠元素不受影响
+    set.set_overwritable(r(5, 25), true).unwrap();
+    assert!(set.iter().all(|e| e.overwritable));
+    assert_eq!(set.validate(), Ok(()));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn alloc_set_overwritable_splits_middle_of_element() {
+    let mut set = Vec::<TestRangeWithKind<i32, i32>>::new();
+    set.merge_add(TestRangeWithKind::new(r(0, 30), 1, true))
+        .unwrap();
+
+    set.set_overwritable(r(10, 20), false).unwrap();
+    assert_eq!(
+        set.as_slice(),
+        &[
+            TestRangeWithKind::new(r(0, 10), 1, true),
+            TestRangeWithKind::new(r(10, 20), 1, false),
+            TestRangeWithKind::new(r(20, 30), 1, true),
+        ]
+    );
+    assert_eq!(set.validate(), Ok(()));
+
+    set.set_overwritable(r(0, 30), true).unwrap();
+    assert_eq!(set.as_slice(), &[TestRangeWithKind::new(r(0, 30), 1, true)]);
+}
+
+#[test]
+fn same_kind_add_keeps_partial_lock() {
+    let mut set = heapless::Vec::<TestRangeWithKind<i32, i32>, 8>::new();
+    let mut temp = temp_buffer();
+    set.merge_add(TestRangeWithKind::new(r(0, 10), 1, true), &mut temp)
+        .unwrap();
+    set.set_overwritable(r(3, 7), false).unwrap();
+
+    // 与锁定片段相接的同 kind 可覆盖区间不会吸收锁定片段，也不会解除锁定
+    set.merge_add(TestRangeWithKind::new(r(0, 1), 1, true), &mut temp)
+        .unwrap();
+    set.merge_add(TestRangeWithKind::new(r(7, 8), 1, true), &mut temp)
+        .unwrap();
+    assert_eq!(
+        set.as_slice(),
+        &[
+            TestRangeWithKind::new(r(0, 3), 1, true),
+            TestRangeWithKind::new(r(3, 7), 1, false),
+            TestRangeWithKind::new(r(7, 10), 1, true),
+        ]
+    );
+    assert!(matches!(
+        set.merge_add(TestRangeWithKind::new(r(4, 5), 2, true), &mut temp),
+        Err(RangeError::Conflict { .. })
+    ));
+
+    // 重复添加相同的可覆盖区间同样保持锁定
+    set.merge_add(TestRangeWithKind::new(r(3, 7), 1, true), &mut temp)
+        .unwrap();
+    assert!(!set[1].overwritable);
+    assert_eq!(set.validate(), Ok(()));
+}
+
+#[test]
+fn identical_add_matches_normal_path() {
+    let mut set = heapless::Vec::<TestRangeWithKind<i32, i32>, 8>::new();
+    let mut temp = temp_buffer();
+    set.merge_add(TestRangeWithKind::new(r(0, 10), 1, true), &mut temp)
+        .unwrap();
+    set.set_overwritable(r(3, 7), false).unwrap();
+
+    // 重复添加相同的锁定区间与添加其中一部分结果相同，都不与两侧可覆盖的元素合并
+    let mut partial = set.clone();
+    partial
+        .merge_add(TestRangeWithKind::new(r(3, 6), 1, false), &mut temp)
+        .unwrap();
+    set.merge_add(TestRangeWithKind::new(r(3, 7), 1, false), &mut temp)
+        .unwrap();
+    assert_eq!(set, partial);
+    assert_eq!(set.len(), 3);
+
+    // chunk_elements 留下的相接片段：相同区间不能跳过，需要与相邻片段合并
+    let mut chunked = heapless::Vec::<TestRangeWithKind<i32, i32>, 8>::new();
+    chunked
+        .merge_add(TestRangeWithKind::new(r(0, 8), 1, true), &mut temp)
+        .unwrap();
+    chunked.chunk_elements(4, &mut temp).unwrap();
+    chunked
+        .merge_add(TestRangeWithKind::new(r(0, 4), 1, true), &mut temp)
+        .unwrap();
+    assert_eq!(
+        chunked.as_slice(),
+        &[TestRangeWithKind::new(r(0, 8), 1, true)]
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn alloc_same_kind_add_keeps_partial_lock() {
+    let mut set = Vec::<TestRangeWithKind<i32, i32>>::new();
+    set.merge_add(TestRangeWithKind::new(r(0, 10), 1, true))
+        .unwrap();
+    set.set_overwritable(r(3, 7), false).unwrap();
+
+    set.merge_add(TestRangeWithKind::new(r(0, 1), 1, true))
+        .unwrap();
+    set.merge_add(TestRangeWithKind::new(r(7, 8), 1, true))
+        .unwrap();
+    assert_eq!(
+        set.as_slice(),
+        &[
+            TestRangeWithKind::new(r(0, 3), 1, true),
+            TestRangeWithKind::new(r(3, 7), 1, false),
+            TestRangeWithKind::new(r(7, 10), 1, true),
+        ]
+    );
+    assert!(matches!(
+        set.merge_add(TestRangeWithKind::new(r(4, 5), 2, true)),
+        Err(RangeError::Conflict { .. })
+    ));
+
+    // 跨过锁定片段的同 kind 区间把所有片段合并为一个，锁定扩展到整个元素
+    set.merge_add(TestRangeWithKind::new(r(2, 8), 1, true))
+        .unwrap();
+    assert_eq!(
+        set.as_slice(),
+        &[TestRangeWithKind::new(r(0, 10), 1, false)]
+    );
+}
+
+#[test]
+fn unit_range_bounds_accessors() {
+    let mut set: RangeSet<UnitRange<i32>> = RangeSet::new();
+    let mut temp = temp_buffer();
+    set.merge_extend(
+        [UnitRange::new(r(0, 5)), UnitRange::new(r(10, 15))],
+        &mut temp,
+    )
+    .unwrap();
+
+    let mut iter = set.iter().peekable();
+    let mut gaps = Vec::new();
+    while let Some(elem) = iter.next() {
+        assert_eq!(elem.start()..elem.end(), elem.range());
+        if let Some(next) = iter.peek() {
+            gaps.push(elem.end()..next.start());
+        }
+    }
+    assert_eq!(gaps, [r(5, 10)]);
+}
+
 #[test]
 fn add_out_of_order_is_normalized() {
     let mut set = heapless::Vec::<TestRange<i32>, 128>::default();
@@ -211,3 +484,429 @@ fn overwritable_ranges_can_be_replaced() {
     ];
     assert_eq!(set.as_slice(), &expected);
 }
+
+#[test]
+fn count_by_kind_groups_consecutive_kinds() {
+    let mut set = heapless::Vec::<TestRangeWithKind<i32, i32>, 16>::new();
+    let mut temp = temp_buffer();
+    set.merge_extend(
+        [
+            TestRangeWithKind::new(r(0, 10), 1, true),
+            TestRangeWithKind::new(r(20, 25), 1, true),
+            TestRangeWithKind::new(r(25, 40), 2, true),
+        ],
+        &mut temp,
+    )
+    .unwrap();
+
+    let counts: std::vec::Vec<_> = set.count_by_kind().collect();
+    assert_eq!(counts, [(1, 2, 15), (2, 1, 15)]);
+}
+
+#[test]
+fn collect_into_smaller_buffer_fails() {
+    let mut set = heapless::Vec::<TestRange<i32>, 16>::new();
+    let mut temp = temp_buffer();
+    set.merge_extend(
+        [
+            TestRange::new(r(0, 5), true),
+            TestRange::new(r(10, 15), true),
+            TestRange::new(r(20, 25), true),
+        ],
+        &mut temp,
+    )
+    .unwrap();
+
+    let mut small = heapless::Vec::<TestRange<i32>, 2>::new();
+    assert_eq!(set.collect_into(&mut small), Err(RangeError::Capacity));
+    assert!(small.is_empty());
+
+    let mut exact = heapless::Vec::<TestRange<i32>, 3>::new();
+    set.collect_into(&mut exact).unwrap();
+    assert_eq!(exact.as_slice(), set.as_slice());
+}
+
+#[test]
+fn copied_ranges_smaller_buffer_fails() {
+    let mut set = heapless::Vec::<TestRange<i32>, 16>::new();
+    let mut temp = temp_buffer();
+    set.merge_extend(
+        [
+            TestRange::new(r(0, 5), true),
+            TestRange::new(r(10, 15), true),
+            TestRange::new(r(20, 25), true),
+        ],
+        &mut temp,
+    )
+    .unwrap();
+
+    assert_eq!(set.copied_ranges::<2>(), Err(RangeError::Capacity));
+
+    let ranges = set.copied_ranges::<3>().unwrap();
+    assert_eq!(ranges.as_slice(), &[r(0, 5), r(10, 15), r(20, 25)]);
+}
+
+#[test]
+fn as_element_slice_borrows_current_contents() {
+    let mut set = heapless::Vec::<TestRange<i32>, 16>::new();
+    let mut temp = temp_buffer();
+    set.merge_add(TestRange::new(r(0, 5), true), &mut temp)
+        .unwrap();
+
+    let view = set.as_element_slice();
+    assert_eq!(view.as_ptr(), set.as_slice().as_ptr());
+    assert_eq!(view, &[TestRange::new(r(0, 5), true)]);
+
+    set.merge_add(TestRange::new(r(5, 10), true), &mut temp)
+        .unwrap();
+    assert_eq!(set.as_element_slice(), &[TestRange::new(r(0, 10), true)]);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn alloc_as_slice_borrows_elements_and_merged_ranges_copies_geometry() {
+    let mut set = Vec::<TestRangeWithKind<i32, i32>>::new();
+    set.merge_extend([
+        TestRangeWithKind::new(r(0, 10), 1, true),
+        TestRangeWithKind::new(r(10, 20), 2, true),
+        TestRangeWithKind::new(r(30, 40), 1, true),
+    ])
+    .unwrap();
+
+    // as_slice 返回元素本身，且指向内部存储，两个后端一致
+    let view = VecOps::as_slice(&set);
+    assert_eq!(view.as_ptr(), set.as_ptr());
+    assert_eq!(view.len(), 3);
+    assert_eq!(view[1].kind, 2);
+
+    // merged_ranges 只保留覆盖范围，相接的不同 kind 元素合并
+    assert_eq!(set.merged_ranges(), [r(0, 20), r(30, 40)]);
+}
+
+#[test]
+fn preview_add_matches_actual_apply() {
+    let mut set = heapless::Vec::<TestRangeWithKind<i32, i32>, 16>::new();
+    let mut temp = temp_buffer();
+    set.merge_extend(
+        [
+            TestRangeWithKind::new(r(0, 10), 1, true),
+            TestRangeWithKind::new(r(20, 30), 1, true),
+        ],
+        &mut temp,
+    )
+    .unwrap();
+    let before = set.clone();
+
+    let info = TestRangeWithKind::new(r(5, 25), 2, true);
+    let mut preview = heapless::Vec::new();
+    set.preview_add(info.clone(), &mut preview, &mut temp)
+        .unwrap();
+    assert_eq!(set, before);
+
+    set.merge_add(info, &mut temp).unwrap();
+    assert_eq!(set, preview);
+}
+
+#[test]
+fn split_hook_can_protect_surviving_fragments() {
+    let mut set = heapless::Vec::<TestRangeWithKind<i32, i32>, 16>::new();
+    let mut temp = temp_buffer();
+    set.merge_add(TestRangeWithKind::new(r(0, 30), 1, true), &mut temp)
+        .unwrap();
+
+    let mut fragments = 0;
+    set.merge_add_with_split_hook(
+        TestRangeWithKind::new(r(10, 20), 2, true),
+        |part| {
+            fragments += 1;
+            part.overwritable = false;
+        },
+        &mut temp,
+    )
+    .unwrap();
+
+    assert_eq!(fragments, 2);
+    assert_eq!(
+        set.as_slice(),
+        &[
+            TestRangeWithKind::new(r(0, 10), 1, false),
+            TestRangeWithKind::new(r(10, 20), 2, true),
+            TestRangeWithKind::new(r(20, 30), 1, false),
+        ]
+    );
+
+    // 片段已不可覆盖
+    let result = set.merge_add(TestRangeWithKind::new(r(5, 15), 3, true), &mut temp);
+    assert!(matches!(result, Err(RangeError::Conflict { .. })));
+}
+
+#[test]
+fn snapshot_into_overwrites_destination() {
+    let mut set = heapless::Vec::<TestRange<i32>, 8>::new();
+    let mut temp = temp_buffer();
+    set.merge_add(TestRange::new(r(0, 5), true), &mut temp)
+        .unwrap();
+
+    let mut dst = heapless::Vec::<TestRange<i32>, 8>::new();
+    dst.merge_add(TestRange::new(r(100, 200), true), &mut temp)
+        .unwrap();
+
+    set.snapshot_into(&mut dst);
+    assert_eq!(dst, set);
+}
+
+#[test]
+fn snapshot_restore_undoes_mutations() {
+    let mut set = heapless::Vec::<TestRangeWithKind<i32, i32>, 8>::new();
+    let mut temp = temp_buffer();
+    set.merge_add(TestRangeWithKind::new(r(0, 10), 1, true), &mut temp)
+        .unwrap();
+    set.merge_add(TestRangeWithKind::new(r(20, 30), 2, true), &mut temp)
+        .unwrap();
+    let before = set.clone();
+
+    let snapshot = set.snapshot();
+    set.merge_add(TestRangeWithKind::new(r(5, 25), 3, true), &mut temp)
+        .unwrap();
+    set.merge_remove(r(0, 2), &mut temp).unwrap();
+    assert_ne!(set, before);
+
+    set.restore(snapshot);
+    assert_eq!(set, before);
+
+    #[cfg(feature = "alloc")]
+    {
+        let mut alloc_set: Vec<_> = before.iter().cloned().collect();
+        let snapshot = alloc_set.snapshot();
+        alloc_set.clear();
+        alloc_set.restore(snapshot);
+        assert_eq!(alloc_set.as_slice(), before.as_slice());
+    }
+}
+
+#[test]
+fn cmp_by_start_sorts_like_the_set() {
+    let mut items = std::vec![
+        TestRange::new(r(30, 40), true),
+        TestRange::new(r(-5, 0), true),
+        TestRange::new(r(10, 20), true),
+    ];
+    items.sort_by(cmp_by_start);
+
+    let mut set = heapless::Vec::<TestRange<i32>, 8>::new();
+    let mut temp = temp_buffer();
+    set.merge_extend(items.iter().rev().cloned(), &mut temp)
+        .unwrap();
+
+    assert_eq!(items.as_slice(), set.as_slice());
+}
+
+#[test]
+fn insert_position_tie_breaks() {
+    let items = [
+        TestRange::new(r(0, 1), true),
+        TestRange::new(r(5, 6), true),
+        TestRange::new(r(5, 7), true),
+        TestRange::new(r(5, 8), true),
+        TestRange::new(r(9, 10), true),
+    ];
+
+    // 下界：第一个起点为 5 的元素之前
+    assert_eq!(find_insert_position(&items, &r(5, 9)), 1);
+    // 上界：最后一个起点为 5 的元素之后
+    assert_eq!(find_insert_position_stable(&items, &r(5, 9)), 4);
+
+    // 没有相同起点时两者一致
+    assert_eq!(find_insert_position(&items, &r(3, 4)), 1);
+    assert_eq!(find_insert_position_stable(&items, &r(3, 4)), 1);
+    assert_eq!(find_insert_position_stable(&items, &r(20, 21)), 5);
+
+    // 依次按上界插入，起点相同的元素保持插入顺序
+    let mut ordered = std::vec::Vec::new();
+    for item in [
+        TestRange::new(r(5, 6), true),
+        TestRange::new(r(0, 1), true),
+        TestRange::new(r(5, 7), true),
+        TestRange::new(r(5, 8), true),
+    ] {
+        let at = find_insert_position_stable(&ordered, &item.range);
+        ordered.insert(at, item);
+    }
+    assert_eq!(ordered, items[..4]);
+}
+
+// 合并时累加访问次数的区间实现
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct HitRange {
+    range: core::ops::Range<i32>,
+    hits: u32,
+}
+
+impl HitRange {
+    fn new(range: core::ops::Range<i32>, hits: u32) -> Self {
+        Self { range, hits }
+    }
+}
+
+impl RangeInfo for HitRange {
+    type Kind = ();
+    type Type = i32;
+
+    fn range(&self) -> core::ops::Range<i32> {
+        self.range.clone()
+    }
+
+    fn kind(&self) {}
+
+    fn overwritable(&self) -> bool {
+        true
+    }
+
+    fn clone_with_range(&self, range: core::ops::Range<i32>) -> Self {
+        Self {
+            range,
+            hits: self.hits,
+        }
+    }
+
+    fn merge_meta(&mut self, other: &Self) {
+        self.hits += other.hits;
+    }
+}
+
+#[test]
+fn merge_meta_accumulates_on_merge() {
+    let mut set = heapless::Vec::<HitRange, 8>::new();
+    let mut temp = temp_buffer();
+    set.merge_add(HitRange::new(r(0, 10), 1), &mut temp)
+        .unwrap();
+    set.merge_add(HitRange::new(r(10, 20), 1), &mut temp)
+        .unwrap();
+    assert_eq!(set.as_slice(), &[HitRange::new(r(0, 20), 2)]);
+
+    // 桥接两个元素时三者的元数据都被累加
+    set.merge_add(HitRange::new(r(30, 40), 4), &mut temp)
+        .unwrap();
+    set.merge_add(HitRange::new(r(15, 35), 1), &mut temp)
+        .unwrap();
+    assert_eq!(set.as_slice(), &[HitRange::new(r(0, 40), 7)]);
+
+    // 重复添加完全相同的区间同样视为合并
+    set.merge_add(HitRange::new(r(0, 40), 1), &mut temp)
+        .unwrap();
+    assert_eq!(set.as_slice(), &[HitRange::new(r(0, 40), 8)]);
+
+    #[cfg(feature = "alloc")]
+    {
+        let mut set = Vec::new();
+        set.merge_add(HitRange::new(r(0, 10), 1)).unwrap();
+        set.merge_add(HitRange::new(r(10, 20), 1)).unwrap();
+        assert_eq!(set, [HitRange::new(r(0, 20), 2)]);
+    }
+}
+
+// 分割时把预算平分给保留下来的片段
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct BudgetRange {
+    range: core::ops::Range<i32>,
+    kind: u8,
+    budget: u32,
+}
+
+impl BudgetRange {
+    fn new(range: core::ops::Range<i32>, kind: u8, budget: u32) -> Self {
+        Self {
+            range,
+            kind,
+            budget,
+        }
+    }
+}
+
+impl RangeInfo for BudgetRange {
+    type Kind = u8;
+    type Type = i32;
+
+    fn range(&self) -> core::ops::Range<i32> {
+        self.range.clone()
+    }
+
+    fn kind(&self) -> u8 {
+        self.kind
+    }
+
+    fn overwritable(&self) -> bool {
+        true
+    }
+
+    fn clone_with_range(&self, range: core::ops::Range<i32>) -> Self {
+        Self {
+            range,
+            kind: self.kind,
+            budget: self.budget,
+        }
+    }
+
+    fn split(&self, at: &core::ops::Range<i32>) -> [Option<Self>; 2] {
+        split_range(self, at).map(|part| {
+            part.map(|mut part| {
+                part.budget = self.budget / 2;
+                part
+            })
+        })
+    }
+}
+
+#[test]
+fn split_override_is_used_for_remove_and_overwrite() {
+    let mut set = heapless::Vec::<BudgetRange, 8>::new();
+    let mut temp = temp_buffer();
+    set.merge_add(BudgetRange::new(r(0, 100), 1, 80), &mut temp)
+        .unwrap();
+
+    set.merge_remove(r(40, 60), &mut temp).unwrap();
+    assert_eq!(
+        set.as_slice(),
+        &[
+            BudgetRange::new(r(0, 40), 1, 40),
+            BudgetRange::new(r(60, 100), 1, 40),
+        ]
+    );
+
+    // 被不同 kind 覆盖时同样经过 split
+    set.merge_add(BudgetRange::new(r(10, 20), 2, 7), &mut temp)
+        .unwrap();
+    assert_eq!(
+        set.as_slice(),
+        &[
+            BudgetRange::new(r(0, 10), 1, 20),
+            BudgetRange::new(r(10, 20), 2, 7),
+            BudgetRange::new(r(20, 40), 1, 20),
+            BudgetRange::new(r(60, 100), 1, 40),
+        ]
+    );
+
+    // 在元素中间插入空白时同样经过 split
+    set.shift_after(70, 10).unwrap();
+    assert_eq!(
+        &set[3..],
+        &[
+            BudgetRange::new(r(60, 70), 1, 20),
+            BudgetRange::new(r(80, 110), 1, 20),
+        ]
+    );
+
+    #[cfg(feature = "alloc")]
+    {
+        let mut alloc_set = vec![BudgetRange::new(r(0, 100), 1, 80)];
+        alloc_set.merge_remove_many([r(10, 20), r(50, 60)]).unwrap();
+        assert_eq!(
+            alloc_set,
+            [
+                BudgetRange::new(r(0, 10), 1, 40),
+                BudgetRange::new(r(20, 50), 1, 20),
+                BudgetRange::new(r(60, 100), 1, 20),
+            ]
+        );
+    }
+}